@@ -21,10 +21,13 @@ mod gui {
 // #[cfg(feature = "gui")]
 pub use gui::*;
 
+pub use tailwag_macro_exports::Builder;
+pub use tailwag_macro_exports::Delegate;
 pub use tailwag_macro_exports::Deref;
 pub use tailwag_macro_exports::DerefMut;
 pub use tailwag_macro_exports::Display;
 pub use tailwag_macro_exports::FromStr;
+pub use tailwag_macro_exports::JsonSchema;
 
 // pub use tailwag_macro_exports::BuildCreateRoute;
 // pub use tailwag_macro_exports::BuildListGetRoute;
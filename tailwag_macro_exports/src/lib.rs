@@ -25,7 +25,7 @@ macro_rules! derive_struct {
     };
 }
 
-#[proc_macro_derive(FromStr)]
+#[proc_macro_derive(FromStr, attributes(from_str, display))]
 pub fn derive_from_str(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input = parse_macro_input!(input);
     let impl_trait_tokens = tailwag_macro_logic::derive::from_string::derive_trait(&input);
@@ -53,22 +53,37 @@ pub fn derive_display(input: proc_macro::TokenStream) -> proc_macro::TokenStream
     impl_trait_tokens.into()
 }
 
+#[proc_macro_derive(Builder, attributes(builder))]
+pub fn derive_builder(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input);
+    let impl_trait_tokens = tailwag_macro_logic::derive::builder::derive_builder(&input);
+    impl_trait_tokens.into()
+}
+
+#[proc_macro_derive(JsonSchema, attributes(json_schema))]
+pub fn derive_json_schema(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input);
+    let impl_trait_tokens = tailwag_macro_logic::derive::json_schema::derive_json_schema(&input);
+    impl_trait_tokens.into()
+}
+
 #[proc_macro_derive(
     BuildRoutes,
     attributes(
         actions,
         views,
-        get,
-        post,
-        patch,
-        delete,
-        get_detail,
         get_policy,
         post_policy,
         list_policy,
         delete_policy,
         patch_policy,
-        delete_policy
+        delete_policy,
+        routes,
+        filterable,
+        sortable,
+        json_schema,
+        id,
+        children
     )
 )]
 pub fn derive_build_routes(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
@@ -91,3 +106,10 @@ pub fn derive_deref(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let impl_trait_tokens = tailwag_macro_logic::derive::deref::derive_deref(&input);
     impl_trait_tokens.into()
 }
+/// Wraps a function with inputs/outputs for a `syn` / `quote`
+#[proc_macro_derive(Delegate, attributes(delegate))]
+pub fn derive_delegate(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input);
+    let impl_trait_tokens = tailwag_macro_logic::derive::delegate::derive_delegate(&input);
+    impl_trait_tokens.into()
+}
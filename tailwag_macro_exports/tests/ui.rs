@@ -0,0 +1,41 @@
+#[test]
+fn deref_ui() {
+    let t = trybuild::TestCases::new();
+    t.pass("tests/ui/deref/pass/*.rs");
+    t.compile_fail("tests/ui/deref/fail/*.rs");
+}
+
+#[test]
+fn display_ui() {
+    let t = trybuild::TestCases::new();
+    t.pass("tests/ui/display/pass/*.rs");
+    t.compile_fail("tests/ui/display/fail/*.rs");
+}
+
+#[test]
+fn from_str_ui() {
+    let t = trybuild::TestCases::new();
+    t.pass("tests/ui/from_str/pass/*.rs");
+    t.compile_fail("tests/ui/from_str/fail/*.rs");
+}
+
+#[test]
+fn builder_ui() {
+    let t = trybuild::TestCases::new();
+    t.pass("tests/ui/builder/pass/*.rs");
+    t.compile_fail("tests/ui/builder/fail/*.rs");
+}
+
+#[test]
+fn delegate_ui() {
+    let t = trybuild::TestCases::new();
+    t.pass("tests/ui/delegate/pass/*.rs");
+    t.compile_fail("tests/ui/delegate/fail/*.rs");
+}
+
+#[test]
+fn json_schema_ui() {
+    let t = trybuild::TestCases::new();
+    t.pass("tests/ui/json_schema/pass/*.rs");
+    t.compile_fail("tests/ui/json_schema/fail/*.rs");
+}
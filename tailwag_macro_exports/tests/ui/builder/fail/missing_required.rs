@@ -0,0 +1,13 @@
+use tailwag_macro_exports::Builder;
+
+#[derive(Builder)]
+struct Brewery {
+    #[builder(required)]
+    name: String,
+    #[builder(required)]
+    founded: u16,
+}
+
+fn main() {
+    let _ = Brewery::builder().name("Hopworks".to_string()).build();
+}
@@ -0,0 +1,14 @@
+use tailwag_macro_exports::Builder;
+
+#[derive(Builder)]
+struct Job {
+    build: u32,
+}
+
+#[derive(Builder)]
+struct Project {
+    #[builder(each = "builder")]
+    builders: Vec<String>,
+}
+
+fn main() {}
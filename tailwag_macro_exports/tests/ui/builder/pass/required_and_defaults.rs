@@ -0,0 +1,53 @@
+use std::collections::HashMap;
+
+use tailwag_macro_exports::Builder;
+
+#[derive(Debug, PartialEq, Builder)]
+struct Brewery {
+    #[builder(required, into)]
+    name: String,
+    #[builder(required)]
+    founded: u16,
+    city: String,
+    #[builder(default = 5)]
+    rating: u8,
+    website: Option<String>,
+    #[builder(each = "beer")]
+    beers: Vec<String>,
+    #[builder(each = "tag")]
+    tags: HashMap<String, String>,
+}
+
+#[derive(Debug, PartialEq, Builder)]
+struct Wrapper<T: Clone> {
+    #[builder(required)]
+    value: T,
+    #[builder(default)]
+    extra: Vec<T>,
+}
+
+fn main() {
+    let brewery = Brewery::builder()
+        .name("Hopworks")
+        .city("Portland".to_string())
+        .founded(2008)
+        .website("hopworks.com".to_string())
+        .beer("IPA".to_string())
+        .beer("Stout".to_string())
+        .tag("style".to_string(), "organic".to_string())
+        .build()
+        .unwrap();
+    assert_eq!(brewery.name, "Hopworks");
+    assert_eq!(brewery.rating, 5);
+    assert_eq!(brewery.website.as_deref(), Some("hopworks.com"));
+    assert_eq!(brewery.beers, ["IPA", "Stout"]);
+    assert_eq!(brewery.tags["style"], "organic");
+
+    // Required fields are checked at compile time, but fields without a default are checked when building.
+    let missing = Brewery::builder().founded(2008).name("Hopworks").build();
+    assert_eq!(missing, Err(BreweryBuilderError::MissingField("city")));
+    assert_eq!(missing.unwrap_err().to_string(), "Missing required field `city` for Brewery");
+
+    let wrapper = Wrapper::builder().value(3).build().unwrap();
+    assert_eq!(wrapper, Wrapper { value: 3, extra: Vec::new() });
+}
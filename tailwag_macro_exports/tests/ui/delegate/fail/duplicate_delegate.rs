@@ -0,0 +1,12 @@
+use tailwag_macro_exports::Delegate;
+
+#[derive(Delegate)]
+#[delegate(IntoIterator)]
+struct Team {
+    #[delegate]
+    members: Vec<String>,
+    #[delegate]
+    scores: Vec<u32>,
+}
+
+fn main() {}
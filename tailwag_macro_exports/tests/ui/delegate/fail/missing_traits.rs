@@ -0,0 +1,6 @@
+use tailwag_macro_exports::Delegate;
+
+#[derive(Delegate)]
+struct Names(Vec<String>);
+
+fn main() {}
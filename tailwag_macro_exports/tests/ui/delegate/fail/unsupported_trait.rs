@@ -0,0 +1,7 @@
+use tailwag_macro_exports::Delegate;
+
+#[derive(Delegate)]
+#[delegate(IntoIterator, Hash)]
+struct Names(Vec<String>);
+
+fn main() {}
@@ -0,0 +1,34 @@
+use std::fmt::Debug;
+
+use tailwag_macro_exports::Delegate;
+
+#[derive(Delegate)]
+#[delegate(IntoIterator, Index, IndexMut, AsRef, AsMut, Extend)]
+struct Stack<T: Clone, U>
+where
+    U: Debug,
+{
+    #[delegate]
+    items: Vec<T>,
+    _label: U,
+}
+
+fn first<C: AsRef<Vec<u8>>>(collection: &C) -> Option<u8> {
+    collection.as_ref().first().copied()
+}
+
+fn main() {
+    let mut stack = Stack {
+        items: vec![1u8, 2],
+        _label: "stack",
+    };
+    stack.extend([3]);
+    stack[0] = 7;
+    stack.as_mut().push(4);
+    assert_eq!(first(&stack), Some(7));
+    assert_eq!((&stack).into_iter().sum::<u8>(), 16);
+    for item in &mut stack {
+        *item += 1;
+    }
+    assert_eq!(stack.into_iter().collect::<Vec<_>>(), [8, 3, 4, 5]);
+}
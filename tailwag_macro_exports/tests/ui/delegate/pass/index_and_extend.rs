@@ -0,0 +1,30 @@
+use std::collections::HashMap;
+
+use tailwag_macro_exports::Delegate;
+
+#[derive(Delegate)]
+#[delegate(Index, IndexMut, Extend)]
+struct Scores {
+    _name: String,
+    #[delegate]
+    scores: Vec<u32>,
+}
+
+#[derive(Delegate)]
+#[delegate(Index, Extend)]
+struct Lookup(HashMap<String, u32>);
+
+fn main() {
+    let mut scores = Scores {
+        _name: "scores".to_string(),
+        scores: vec![1, 2],
+    };
+    scores[0] = 10;
+    scores.extend([3, 4]);
+    assert_eq!(scores[0], 10);
+    assert_eq!(scores[1..], [2, 3, 4]);
+
+    let mut lookup = Lookup(HashMap::new());
+    lookup.extend([("a".to_string(), 1)]);
+    assert_eq!(lookup["a"], 1);
+}
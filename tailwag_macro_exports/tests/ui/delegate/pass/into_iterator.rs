@@ -0,0 +1,19 @@
+use tailwag_macro_exports::Delegate;
+
+#[derive(Delegate)]
+#[delegate(IntoIterator)]
+struct Names(Vec<String>);
+
+fn main() {
+    let mut names = Names(vec!["a".to_string(), "b".to_string()]);
+
+    for name in &mut names {
+        name.push('!');
+    }
+
+    let borrowed: Vec<&String> = (&names).into_iter().collect();
+    assert_eq!(borrowed, ["a!", "b!"]);
+
+    let owned: Vec<String> = names.into_iter().collect();
+    assert_eq!(owned, ["a!", "b!"]);
+}
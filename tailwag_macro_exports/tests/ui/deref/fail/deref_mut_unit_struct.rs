@@ -0,0 +1,6 @@
+use tailwag_macro_exports::{Deref, DerefMut};
+
+#[derive(Deref, DerefMut)]
+struct Unit;
+
+fn main() {}
@@ -0,0 +1,11 @@
+use tailwag_macro_exports::Deref;
+
+#[derive(Deref)]
+struct User {
+    #[deref]
+    id: u32,
+    #[deref]
+    name: String,
+}
+
+fn main() {}
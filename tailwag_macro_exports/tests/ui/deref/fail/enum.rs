@@ -0,0 +1,8 @@
+use tailwag_macro_exports::Deref;
+
+#[derive(Deref)]
+enum Status {
+    Active,
+}
+
+fn main() {}
@@ -0,0 +1,6 @@
+use tailwag_macro_exports::Deref;
+
+#[derive(Deref)]
+struct Name(#[deref(forward, target = "str")] String);
+
+fn main() {}
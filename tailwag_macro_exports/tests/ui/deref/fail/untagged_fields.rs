@@ -0,0 +1,9 @@
+use tailwag_macro_exports::Deref;
+
+#[derive(Deref)]
+struct User {
+    id: u32,
+    name: String,
+}
+
+fn main() {}
@@ -0,0 +1,37 @@
+use std::fmt::Debug;
+
+use tailwag_macro_exports::{Deref, DerefMut};
+
+#[derive(Deref, DerefMut)]
+struct Wrapper<T>(T);
+
+#[derive(Deref, DerefMut)]
+struct Bounded<T: Clone, U>
+where
+    U: Debug,
+{
+    #[deref]
+    items: Vec<T>,
+    _other: U,
+}
+
+#[derive(Deref, DerefMut)]
+struct Borrowed<'a, T: ?Sized>(&'a mut T);
+
+fn main() {
+    let mut wrapper = Wrapper(1u8);
+    *wrapper += 1;
+    assert_eq!(*wrapper, 2);
+
+    let mut bounded = Bounded {
+        items: vec![3, 1, 2],
+        _other: "debug",
+    };
+    bounded.sort();
+    assert_eq!(*bounded, vec![1, 2, 3]);
+
+    let mut name = String::from("borrowed");
+    let mut borrowed = Borrowed(&mut name);
+    borrowed.push('!');
+    assert_eq!(name, "borrowed!");
+}
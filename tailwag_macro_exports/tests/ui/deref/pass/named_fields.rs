@@ -0,0 +1,28 @@
+use tailwag_macro_exports::{Deref, DerefMut};
+
+#[derive(Deref, DerefMut)]
+struct Single {
+    name: String,
+}
+
+#[derive(Deref, DerefMut)]
+struct Tagged {
+    _id: u32,
+    #[deref]
+    name: String,
+}
+
+fn main() {
+    let mut single = Single {
+        name: "single".to_string(),
+    };
+    single.push('!');
+    assert_eq!(*single, "single!");
+
+    let mut tagged = Tagged {
+        _id: 1,
+        name: "tagged".to_string(),
+    };
+    tagged.push('!');
+    assert_eq!(*tagged, "tagged!");
+}
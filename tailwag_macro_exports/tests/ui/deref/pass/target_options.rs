@@ -0,0 +1,30 @@
+use std::{borrow::Borrow, collections::HashSet};
+
+use tailwag_macro_exports::{Deref, DerefMut};
+
+#[derive(Deref, DerefMut, PartialEq, Eq, Hash)]
+struct Name(#[deref(target = "str", as_ref, borrow)] String);
+
+#[derive(Deref, DerefMut)]
+struct Items<T>(#[deref(target = "[T]")] Vec<T>);
+
+#[derive(Deref, DerefMut)]
+struct Boxed(#[deref(forward)] Box<String>);
+
+fn main() {
+    let mut name = Name("name".to_string());
+    name.make_ascii_uppercase();
+    let as_ref: &str = name.as_ref();
+    let borrowed: &str = name.borrow();
+    assert_eq!(as_ref, "NAME");
+    assert_eq!(borrowed, "NAME");
+    assert!(HashSet::from([name]).contains("NAME"));
+
+    let mut items = Items(vec![2, 1]);
+    items.sort();
+    assert_eq!(&*items, &[1, 2]);
+
+    let mut boxed = Boxed(Box::new("boxed".to_string()));
+    boxed.push('!');
+    assert_eq!(boxed.as_str(), "boxed!");
+}
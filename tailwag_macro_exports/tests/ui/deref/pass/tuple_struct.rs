@@ -0,0 +1,18 @@
+use tailwag_macro_exports::{Deref, DerefMut};
+
+#[derive(Deref, DerefMut)]
+struct UserId(u64);
+
+#[derive(Deref, DerefMut)]
+struct Labeled(u8, #[deref] String);
+
+fn main() {
+    let mut id = UserId(1);
+    *id += 1;
+    assert_eq!(*id, 2);
+
+    let mut labeled = Labeled(0, "label".to_string());
+    labeled.push('!');
+    assert_eq!(labeled.len(), 6);
+    assert_eq!(labeled.0, 0);
+}
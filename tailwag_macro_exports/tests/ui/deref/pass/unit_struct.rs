@@ -0,0 +1,12 @@
+use tailwag_macro_exports::Deref;
+
+#[derive(Deref)]
+struct Unit;
+
+#[derive(Deref)]
+struct Empty {}
+
+fn main() {
+    assert_eq!(&*Unit, "Unit");
+    assert_eq!(&*Empty {}, "Empty");
+}
@@ -0,0 +1,9 @@
+use tailwag_macro_exports::Display;
+
+#[derive(Display)]
+#[display("{kind}")]
+struct Labeled {
+    r#type: String,
+}
+
+fn main() {}
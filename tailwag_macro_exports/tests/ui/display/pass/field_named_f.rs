@@ -0,0 +1,29 @@
+use tailwag_macro_exports::Display;
+
+// A field named `f` mustn't shadow the formatter that's written to.
+#[derive(Display)]
+#[display("{f}")]
+struct Point {
+    f: i32,
+}
+
+#[derive(Display)]
+#[display(payload)]
+enum Outcome {
+    Failed { f: String },
+}
+
+#[derive(Display)]
+enum Labeled {
+    #[display("{f}/{formatter}")]
+    Pair { f: u8, formatter: u8 },
+}
+
+fn main() {
+    assert_eq!(Point { f: 3 }.to_string(), "3");
+    let failed = Outcome::Failed {
+        f: "timeout".to_string(),
+    };
+    assert_eq!(failed.to_string(), "Failed { f: timeout }");
+    assert_eq!(Labeled::Pair { f: 1, formatter: 2 }.to_string(), "1/2");
+}
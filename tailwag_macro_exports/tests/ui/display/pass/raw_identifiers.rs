@@ -0,0 +1,36 @@
+use tailwag_macro_exports::Display;
+
+#[derive(Display)]
+#[display("{r#type} ({name})")]
+struct Labeled {
+    r#type: String,
+    name: String,
+}
+
+#[derive(Display)]
+#[display("<{type}>")]
+struct Unprefixed {
+    r#type: String,
+}
+
+#[derive(Display)]
+#[display(payload)]
+enum Token {
+    Keyword { r#type: String },
+}
+
+fn main() {
+    let labeled = Labeled {
+        r#type: "ale".to_string(),
+        name: "Hazy".to_string(),
+    };
+    assert_eq!(labeled.to_string(), "ale (Hazy)");
+    let unprefixed = Unprefixed {
+        r#type: "stout".to_string(),
+    };
+    assert_eq!(unprefixed.to_string(), "<stout>");
+    let token = Token::Keyword {
+        r#type: "fn".to_string(),
+    };
+    assert_eq!(token.to_string(), "Keyword { type: fn }");
+}
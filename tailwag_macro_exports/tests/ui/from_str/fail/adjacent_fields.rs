@@ -0,0 +1,10 @@
+use tailwag_macro_exports::FromStr;
+
+#[derive(FromStr)]
+#[from_str("{major}{minor}")]
+struct Version {
+    major: u32,
+    minor: u32,
+}
+
+fn main() {}
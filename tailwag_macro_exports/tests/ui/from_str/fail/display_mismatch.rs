@@ -0,0 +1,10 @@
+use tailwag_macro_exports::{Display, FromStr};
+
+#[derive(Display, FromStr)]
+enum Range {
+    #[from_str("range:{0}-{1}")]
+    #[display("{0}..{1}")]
+    Between(u32, u32),
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+use tailwag_macro_exports::{Display, FromStr};
+
+#[derive(Display, FromStr)]
+#[from_str(case_insensitive)]
+enum Style {
+    #[from_str(alias = "ipa")]
+    PaleAle,
+    #[from_str(alias = ["IPA", "porter"])]
+    Stout,
+}
+
+fn main() {}
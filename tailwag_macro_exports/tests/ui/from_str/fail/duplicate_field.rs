@@ -0,0 +1,7 @@
+use tailwag_macro_exports::FromStr;
+
+#[derive(FromStr)]
+#[from_str("{0}-{0}")]
+struct Pair(u32);
+
+fn main() {}
@@ -0,0 +1,11 @@
+use tailwag_macro_exports::FromStr;
+
+#[derive(FromStr)]
+#[from_str("{host}:{port}")]
+struct Endpoint {
+    name: String,
+    host: String,
+    port: u16,
+}
+
+fn main() {}
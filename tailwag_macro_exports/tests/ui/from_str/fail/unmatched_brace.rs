@@ -0,0 +1,10 @@
+use tailwag_macro_exports::FromStr;
+
+#[derive(FromStr)]
+#[from_str("{key}}={value}")]
+struct Entry {
+    key: String,
+    value: i32,
+}
+
+fn main() {}
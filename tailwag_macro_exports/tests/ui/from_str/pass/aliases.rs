@@ -0,0 +1,24 @@
+use tailwag_macro_exports::{Display, FromStr};
+
+#[derive(Debug, PartialEq, Display, FromStr)]
+#[display(rename_all = "snake_case")]
+#[from_str(case_insensitive)]
+enum Style {
+    #[from_str(alias = "ipa")]
+    PaleAle,
+    #[from_str(alias = ["porter", "dry stout"])]
+    Stout,
+}
+
+fn main() {
+    assert_eq!("pale_ale".parse(), Ok(Style::PaleAle));
+    assert_eq!("IPA".parse(), Ok(Style::PaleAle));
+    assert_eq!("Stout".parse(), Ok(Style::Stout));
+    assert_eq!("PORTER".parse(), Ok(Style::Stout));
+    assert_eq!("Dry Stout".parse(), Ok(Style::Stout));
+
+    let error = "lager".parse::<Style>().unwrap_err();
+    assert_eq!(error.input, "lager");
+    assert_eq!(error.valid_variants, ["pale_ale", "stout"]);
+    assert_eq!(error.to_string(), "\"lager\" is not a valid Style. Expected one of: pale_ale, stout");
+}
@@ -0,0 +1,17 @@
+use tailwag_macro_exports::{Display, FromStr};
+
+#[derive(Debug, PartialEq, Display, FromStr)]
+enum Size {
+    Pint,
+    #[from_str(alias = "half")]
+    HalfPint,
+}
+
+fn main() {
+    assert_eq!("Pint".parse(), Ok(Size::Pint));
+    assert_eq!("half".parse(), Ok(Size::HalfPint));
+
+    let error = "pint".parse::<Size>().unwrap_err();
+    assert_eq!(error.input, "pint");
+    assert_eq!(error.valid_variants, ["Pint", "HalfPint"]);
+}
@@ -0,0 +1,31 @@
+use tailwag_macro_exports::FromStr;
+
+#[derive(Debug, PartialEq, FromStr)]
+#[from_str("{{{key}={value}}}")]
+struct Entry {
+    key: String,
+    value: i32,
+}
+
+#[derive(Debug, PartialEq, FromStr)]
+#[from_str("{type}:{r#ref}")]
+struct Keywords {
+    r#type: String,
+    r#ref: u8,
+}
+
+fn main() {
+    assert_eq!(
+        "{answer=42}".parse::<Entry>().unwrap(),
+        Entry {
+            key: "answer".to_string(),
+            value: 42,
+        }
+    );
+    assert!("answer=42".parse::<Entry>().is_err());
+    assert!("{answer=42".parse::<Entry>().is_err());
+
+    let keywords: Keywords = "fn:7".parse().unwrap();
+    assert_eq!(keywords.r#type, "fn");
+    assert_eq!(keywords.r#ref, 7);
+}
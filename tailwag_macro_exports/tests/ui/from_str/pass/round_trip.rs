@@ -0,0 +1,20 @@
+use tailwag_macro_exports::{Display, FromStr};
+
+#[derive(Debug, PartialEq, Display, FromStr)]
+#[display(rename_all = "snake_case", payload)]
+enum Range {
+    Everything,
+    #[from_str("range:{0}-{1}")]
+    Between(u32, u32),
+    #[from_str("at least {min}")]
+    #[display("at least {min}")]
+    AtLeast { min: u32 },
+}
+
+fn main() {
+    for range in [Range::Everything, Range::Between(1, 2), Range::AtLeast { min: 3 }] {
+        assert_eq!(range.to_string().parse::<Range>().unwrap(), range);
+    }
+    assert_eq!(Range::Between(1, 2).to_string(), "range:1-2");
+    assert_eq!(Range::AtLeast { min: 3 }.to_string(), "at least 3");
+}
@@ -0,0 +1,33 @@
+use tailwag_macro_exports::FromStr;
+
+#[derive(Debug, PartialEq, FromStr)]
+#[from_str(format = "{name}@{host}:{port}")]
+struct Endpoint {
+    name: String,
+    host: String,
+    port: u16,
+}
+
+#[derive(Debug, PartialEq, FromStr)]
+#[from_str("{0}x{1}")]
+struct Size(u32, u32);
+
+fn main() {
+    let endpoint: Endpoint = "api@localhost:8080".parse().unwrap();
+    assert_eq!(
+        endpoint,
+        Endpoint {
+            name: "api".to_string(),
+            host: "localhost".to_string(),
+            port: 8080,
+        }
+    );
+    assert_eq!("3x4".parse::<Size>().unwrap(), Size(3, 4));
+
+    let err = "api@localhost".parse::<Endpoint>().unwrap_err();
+    assert_eq!(err.reason, "expected \":\"");
+    let err = "api@localhost:http".parse::<Endpoint>().unwrap_err();
+    assert!(err.reason.starts_with("invalid value for `port`"));
+    let err = "3x4x5".parse::<Size>().unwrap_err();
+    assert!(err.reason.starts_with("invalid value for field 1"));
+}
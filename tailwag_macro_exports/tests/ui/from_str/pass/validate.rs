@@ -0,0 +1,34 @@
+use tailwag_macro_exports::FromStr;
+
+fn not_empty(name: &String) -> Result<(), &'static str> {
+    match name.trim().is_empty() {
+        true => Err("name can't be empty"),
+        false => Ok(()),
+    }
+}
+
+#[derive(Debug, FromStr)]
+#[from_str(validate = not_empty)]
+struct Name(String);
+
+fn even(value: &u32) -> Result<(), String> {
+    match value % 2 {
+        0 => Ok(()),
+        _ => Err(format!("{} is odd", value)),
+    }
+}
+
+#[derive(Debug, FromStr)]
+#[from_str(validate = even)]
+struct Even(u32);
+
+fn main() {
+    assert_eq!("Hazy".parse::<Name>().unwrap().0, "Hazy");
+    let err = "  ".parse::<Name>().unwrap_err();
+    assert!(matches!(err, ParseNameError::Invalid(_)));
+    assert_eq!(err.to_string(), "name can't be empty");
+
+    assert_eq!("4".parse::<Even>().unwrap().0, 4);
+    assert_eq!("3".parse::<Even>().unwrap_err().to_string(), "3 is odd");
+    assert!(matches!("three".parse::<Even>().unwrap_err(), ParseEvenError::Parse(_)));
+}
@@ -0,0 +1,10 @@
+use tailwag_macro_exports::JsonSchema;
+
+struct Ulid;
+
+#[derive(JsonSchema)]
+struct Keg {
+    id: Ulid,
+}
+
+fn main() {}
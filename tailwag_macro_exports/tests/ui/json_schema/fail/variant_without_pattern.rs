@@ -0,0 +1,9 @@
+use tailwag_macro_exports::JsonSchema;
+
+#[derive(JsonSchema)]
+enum Volume {
+    Empty,
+    Litres(f64),
+}
+
+fn main() {}
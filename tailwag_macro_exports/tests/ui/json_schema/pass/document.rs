@@ -0,0 +1,66 @@
+use std::collections::{HashMap, HashSet};
+
+use serde_json::json;
+use tailwag_macro_exports::{Display, JsonSchema};
+
+#[derive(Display, JsonSchema)]
+#[display(rename_all = "snake_case")]
+enum Style {
+    PaleAle,
+    Stout,
+}
+
+#[derive(JsonSchema)]
+struct Address {
+    city: String,
+}
+
+#[derive(JsonSchema)]
+struct Brewery {
+    name: String,
+    founded: Option<i32>,
+    tags: Vec<String>,
+    styles: HashSet<Style>,
+    ratings: HashMap<String, f64>,
+    address: Address,
+    locations: Vec<Self>,
+}
+
+fn main() {
+    assert_eq!(
+        Style::json_schema(),
+        json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "title": "Style",
+            "type": "string",
+            "enum": ["pale_ale", "stout"],
+        })
+    );
+    assert_eq!(
+        Brewery::json_schema(),
+        json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "title": "Brewery",
+            "type": "object",
+            "properties": {
+                "name": { "type": "string" },
+                "founded": { "anyOf": [{ "type": "integer", "format": "int32" }, { "type": "null" }] },
+                "tags": { "type": "array", "items": { "type": "string" } },
+                "styles": {
+                    "type": "array",
+                    "uniqueItems": true,
+                    "items": { "title": "Style", "type": "string", "enum": ["pale_ale", "stout"] },
+                },
+                "ratings": { "type": "object", "additionalProperties": { "type": "number", "format": "double" } },
+                "address": {
+                    "title": "Address",
+                    "type": "object",
+                    "properties": { "city": { "type": "string" } },
+                    "required": ["city"],
+                },
+                "locations": { "type": "array", "items": { "$ref": "#" } },
+            },
+            "required": ["name", "tags", "styles", "ratings", "address", "locations"],
+        })
+    );
+}
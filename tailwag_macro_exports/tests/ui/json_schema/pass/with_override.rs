@@ -0,0 +1,24 @@
+use serde_json::json;
+use tailwag_macro_exports::JsonSchema;
+
+struct Ulid;
+
+fn ulid_schema() -> serde_json::Value {
+    json!({ "type": "string", "minLength": 26, "maxLength": 26 })
+}
+
+#[derive(JsonSchema)]
+struct Keg(#[json_schema(with = ulid_schema)] Ulid);
+
+fn main() {
+    assert_eq!(
+        Keg::json_schema(),
+        json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "title": "Keg",
+            "type": "string",
+            "minLength": 26,
+            "maxLength": 26,
+        })
+    );
+}
@@ -0,0 +1,362 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{Attribute, Data, DeriveInput, Expr, Field, GenericParam, Generics, Ident, Type};
+
+use crate::util::{
+    attribute_parsing::{as_flag, as_key_value, as_lit_str, get_attribute_args, GetAttribute},
+    type_parsing::{get_type_arguments, is_option},
+};
+
+/// Names used by the generated `builder()` / `build()` methods, which setters can't share.
+const RESERVED_NAMES: [&str; 2] = ["build", "builder"];
+
+/// Options parsed from the `#[builder(...)]` attribute on a field.
+#[derive(Default)]
+struct BuilderFieldOptions {
+    /// `#[builder(required)]` - the field must be set before `build()` is available, enforced at compile time.
+    required: bool,
+    /// `#[builder(default)]` / `#[builder(default = expr)]` - the value used when the field isn't set.
+    /// `Some(None)` means `Default::default()`.
+    default: Option<Option<Expr>>,
+    /// `#[builder(into)]` - the setter accepts anything that converts `Into` the field's type.
+    into: bool,
+    /// `#[builder(each = "tag")]` - also generate a setter that adds a single item to a collection field.
+    each: Option<Ident>,
+}
+
+impl BuilderFieldOptions {
+    fn from_attribute(attr: Option<&Attribute>) -> syn::Result<Self> {
+        let mut options = Self::default();
+        let Some(attr) = attr else {
+            return Ok(options);
+        };
+        for arg in get_attribute_args(attr)? {
+            match (as_flag(&arg), as_key_value(&arg)) {
+                (Some(flag), _) if flag == "required" => options.required = true,
+                (Some(flag), _) if flag == "default" => options.default = Some(None),
+                (Some(flag), _) if flag == "into" => options.into = true,
+                (_, Some((key, value))) if key == "default" => options.default = Some(Some(value.clone())),
+                (_, Some((key, value))) if key == "each" => {
+                    let each = as_lit_str(value)
+                        .ok_or_else(|| syn::Error::new_spanned(value, "Expected a method name as a string"))?;
+                    options.each = Some(each.parse()?);
+                },
+                _ => return Err(syn::Error::new_spanned(arg, "Unrecognized builder option")),
+            }
+        }
+        if options.required && options.default.is_some() {
+            return Err(syn::Error::new_spanned(attr, "A field can't be both `required` and have a `default`"));
+        }
+        if options.required && options.each.is_some() {
+            return Err(syn::Error::new_spanned(attr, "A field can't be both `required` and have an `each` setter"));
+        }
+        Ok(options)
+    }
+}
+
+/// A field of the struct being built, along with its options.
+struct BuilderField<'a> {
+    ident: &'a Ident,
+    field: &'a Field,
+    options: BuilderFieldOptions,
+    /// The typestate parameter tracking whether a required field has been set: `()` while unset, and the field's
+    /// type once it has been.
+    state_param: Option<Ident>,
+    /// The `T` of an `Option<T>` field, which is stored as-is and left `None` when not set.
+    option_inner: Option<&'a Type>,
+}
+
+impl BuilderField<'_> {
+    /// The type the builder stores for this field.
+    fn builder_type(&self) -> TokenStream {
+        let field_type = &self.field.ty;
+        match (&self.state_param, self.option_inner) {
+            (Some(state_param), _) => quote!(#state_param),
+            (None, Some(_)) => quote!(#field_type),
+            (None, None) => quote!(::std::option::Option<#field_type>),
+        }
+    }
+}
+
+/// The arguments to refer to a set of generics, e.g. `'a, T, N` for `<'a, T: Clone, const N: usize>`.
+fn generic_arguments(generics: &Generics) -> Vec<TokenStream> {
+    generics
+        .params
+        .iter()
+        .map(|param| match param {
+            GenericParam::Lifetime(param) => {
+                let lifetime = &param.lifetime;
+                quote!(#lifetime)
+            },
+            GenericParam::Type(param) => {
+                let ident = &param.ident;
+                quote!(#ident)
+            },
+            GenericParam::Const(param) => {
+                let ident = &param.ident;
+                quote!(#ident)
+            },
+        })
+        .collect()
+}
+
+fn build_builder(input: &DeriveInput) -> syn::Result<TokenStream> {
+    let &DeriveInput {
+        ident,
+        vis,
+        generics,
+        data,
+        ..
+    } = &input;
+    let Data::Struct(data) = data else {
+        return Err(syn::Error::new_spanned(ident, "Builder can only be derived for structs"));
+    };
+    let syn::Fields::Named(fields) = &data.fields else {
+        return Err(syn::Error::new_spanned(ident, "Builder can only be derived for structs with named fields"));
+    };
+
+    let mut required_count: usize = 0;
+    let fields = fields
+        .named
+        .iter()
+        .map(|field| {
+            let options = BuilderFieldOptions::from_attribute(field.get_attribute("builder"))?;
+            let mut setter_names = field.ident.iter().chain(options.each.as_ref());
+            if let Some(name) = setter_names.find(|name| RESERVED_NAMES.iter().any(|reserved| name == reserved)) {
+                return Err(syn::Error::new_spanned(
+                    name,
+                    format!("`{}` is reserved for the builder's own methods, and can't be used as a setter name", name),
+                ));
+            }
+            let state_param = options.required.then(|| {
+                required_count += 1;
+                format_ident!("__R{}", required_count - 1)
+            });
+            let option_inner = match is_option(field) && !options.required {
+                true => get_type_arguments(&field.ty).first().copied(),
+                false => None,
+            };
+            Ok(BuilderField {
+                ident: field.ident.as_ref().expect("Found named field with no value for ident"),
+                field,
+                options,
+                state_param,
+                option_inner,
+            })
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    let builder_ident = format_ident!("{}Builder", ident);
+    let error_ident = format_ident!("{}BuilderError", ident);
+    let state_params: Vec<&Ident> = fields.iter().filter_map(|f| f.state_param.as_ref()).collect();
+    let unset_states = state_params.iter().map(|_| quote!(()));
+    let set_states = fields.iter().filter(|f| f.options.required).map(|f| &f.field.ty);
+    let field_idents: Vec<&Ident> = fields.iter().map(|f| f.ident).collect();
+
+    // The builder takes the struct's own generics followed by the typestate params. Defaults are dropped, since
+    // they'd otherwise come before params without one.
+    let mut struct_generics = generics.clone();
+    for param in struct_generics.params.iter_mut() {
+        match param {
+            GenericParam::Type(param) => {
+                param.eq_token = None;
+                param.default = None;
+            },
+            GenericParam::Const(param) => {
+                param.eq_token = None;
+                param.default = None;
+            },
+            GenericParam::Lifetime(_) => (),
+        }
+    }
+    let struct_params: Vec<&GenericParam> = struct_generics.params.iter().collect();
+    let struct_args = generic_arguments(generics);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    // Required fields are stored as their typestate, so the struct's generics might not otherwise be used.
+    let (marker_field, marker_value) = match generics.params.is_empty() {
+        true => (None, None),
+        false => (
+            Some(quote!(__marker: ::std::marker::PhantomData<fn() -> #ident #ty_generics>,)),
+            Some(quote!(__marker: ::std::marker::PhantomData,)),
+        ),
+    };
+
+    let builder_fields = fields.iter().map(|f| {
+        let field_ident = f.ident;
+        let builder_type = f.builder_type();
+        quote!(#field_ident: #builder_type)
+    });
+    let initial_values = fields.iter().map(|f| {
+        let field_ident = f.ident;
+        match f.options.required {
+            true => quote!(#field_ident: ()),
+            false => quote!(#field_ident: ::std::option::Option::None),
+        }
+    });
+
+    let mut setters = Vec::new();
+    for f in &fields {
+        let field_ident = f.ident;
+        let field_type = &f.field.ty;
+        let doc = format!("Sets `{}`.", field_ident);
+        // `Option<T>` fields are set with a `T`.
+        let setter_type = f.option_inner.unwrap_or(field_type);
+        let (arg_type, value) = match f.options.into {
+            true => (quote!(impl ::std::convert::Into<#setter_type>), quote!(#field_ident.into())),
+            false => (quote!(#setter_type), quote!(#field_ident)),
+        };
+        setters.push(match &f.state_param {
+            // Setting a required field changes its typestate, so the builder is rebuilt with the new type.
+            Some(state_param) => {
+                let next_states = state_params.iter().map(|p| match p == &state_param {
+                    true => quote!(#field_type),
+                    false => quote!(#p),
+                });
+                let other_fields = field_idents.iter().filter(|i| *i != &field_ident);
+                let marker = marker_field.as_ref().map(|_| quote!(__marker: self.__marker,));
+                quote!(
+                    #[doc = #doc]
+                    #vis fn #field_ident(self, #field_ident: #arg_type) -> #builder_ident<#(#struct_args,)* #(#next_states),*> {
+                        #builder_ident {
+                            #field_ident: #value,
+                            #(#other_fields: self.#other_fields,)*
+                            #marker
+                        }
+                    }
+                )
+            },
+            None => quote!(
+                #[doc = #doc]
+                #vis fn #field_ident(mut self, #field_ident: #arg_type) -> Self {
+                    self.#field_ident = ::std::option::Option::Some(#value);
+                    self
+                }
+            ),
+        });
+
+        let Some(each) = &f.options.each else {
+            continue;
+        };
+        if each == field_ident {
+            return Err(syn::Error::new_spanned(
+                each,
+                "The `each` setter needs a different name than the field, which already has a setter",
+            ));
+        }
+        let doc = format!("Adds a single item to `{}`.", field_ident);
+        let collection = quote!(self.#field_ident.get_or_insert_with(::std::default::Default::default));
+        setters.push(match get_type_arguments(setter_type).as_slice() {
+            [item] => quote!(
+                #[doc = #doc]
+                #vis fn #each(mut self, item: #item) -> Self {
+                    ::std::iter::Extend::extend(#collection, ::std::iter::once(item));
+                    self
+                }
+            ),
+            [key, value] => quote!(
+                #[doc = #doc]
+                #vis fn #each(mut self, key: #key, value: #value) -> Self {
+                    ::std::iter::Extend::extend(#collection, ::std::iter::once((key, value)));
+                    self
+                }
+            ),
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    field_type,
+                    "`each` is only supported on collections like `Vec<T>` or `HashMap<K, V>`",
+                ))
+            },
+        });
+    }
+
+    let build_values = fields.iter().map(|f| {
+        let field_ident = f.ident;
+        let field_str = field_ident.to_string();
+        match (&f.options.default, f.options.required, f.option_inner.is_some()) {
+            (_, true, _) => quote!(#field_ident: self.#field_ident),
+            (Some(Some(default)), _, true) => quote!(#field_ident: self.#field_ident.or_else(|| #default)),
+            (_, _, true) => quote!(#field_ident: self.#field_ident),
+            (Some(Some(default)), _, _) => quote!(#field_ident: self.#field_ident.unwrap_or_else(|| #default)),
+            // Collections with an `each` setter start out empty.
+            (Some(None), _, _) => quote!(#field_ident: self.#field_ident.unwrap_or_default()),
+            (None, _, _) if f.options.each.is_some() => quote!(#field_ident: self.#field_ident.unwrap_or_default()),
+            (None, _, _) => quote!(
+                #field_ident: self.#field_ident.ok_or(#error_ident::MissingField(#field_str))?
+            ),
+        }
+    });
+
+    let builder_doc = format!("A builder for [`{}`], created with [`{}::builder()`].", ident, ident);
+    let builder_fn_doc = format!("Creates a [`{}`], to set the fields of a [`{}`] one at a time.", builder_ident, ident);
+    let error_doc = format!("The error returned when a [`{}`] is built with fields missing.", builder_ident);
+    let missing_field_msg = format!("Missing required field `{{}}` for {}", ident);
+
+    let tokens = quote!(
+        #[doc = #builder_doc]
+        #[must_use]
+        #vis struct #builder_ident<#(#struct_params,)* #(#state_params),*> #where_clause {
+            #(#builder_fields,)*
+            #marker_field
+        }
+
+        #[doc = #error_doc]
+        #[derive(Debug, Clone, PartialEq, Eq)]
+        #vis enum #error_ident {
+            /// A field with no default was never set.
+            MissingField(&'static str),
+        }
+
+        impl ::std::fmt::Display for #error_ident {
+            fn fmt(
+                &self,
+                f: &mut std::fmt::Formatter<'_>,
+            ) -> std::fmt::Result {
+                match self {
+                    Self::MissingField(field) => write!(f, #missing_field_msg, field),
+                }
+            }
+        }
+
+        impl ::std::error::Error for #error_ident {}
+
+        impl #impl_generics #ident #ty_generics #where_clause {
+            #[doc = #builder_fn_doc]
+            #vis fn builder() -> #builder_ident<#(#struct_args,)* #(#unset_states),*> {
+                #builder_ident {
+                    #(#initial_values,)*
+                    #marker_value
+                }
+            }
+        }
+
+        impl<#(#struct_params,)* #(#state_params),*> #builder_ident<#(#struct_args,)* #(#state_params),*> #where_clause {
+            #(#setters)*
+        }
+
+        impl #impl_generics #builder_ident<#(#struct_args,)* #(#set_states),*> #where_clause {
+            /// Builds the item, using the defaults for any fields that weren't set.
+            #vis fn build(self) -> ::std::result::Result<#ident #ty_generics, #error_ident> {
+                ::std::result::Result::Ok(#ident {
+                    #(#build_values,)*
+                })
+            }
+        }
+    );
+    Ok(tokens)
+}
+
+/// Logic for deriving Builder - supports structs with named fields, including generic ones.
+///
+/// Generates a `{Struct}Builder` with a chained setter for each field, created with `Struct::builder()`:
+///  - `#[builder(required)]` fields must be set before `build()` can be called, which is enforced at compile time.
+///  - `#[builder(default)]` / `#[builder(default = expr)]` fields fall back to `Default::default()` / `expr`.
+///  - `Option<T>` fields are optional, and their setters take a plain `T`.
+///  - Any other field must be set before building, or `build()` returns a `{Struct}BuilderError::MissingField`.
+///  - `#[builder(into)]` setters accept anything that converts `Into` the field's type.
+///  - `#[builder(each = "tag")]` also generates a `tag(item)` setter that adds one item to a `Vec<T>` (or
+///    `tag(key, value)` for a `HashMap<K, V>`). These fields default to empty.
+///
+/// Fields (and `each` setters) can't be named `build` or `builder`, which are taken by the generated methods.
+pub fn derive_builder(input: &DeriveInput) -> TokenStream {
+    build_builder(input).unwrap_or_else(syn::Error::into_compile_error)
+}
@@ -0,0 +1,253 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{ext::IdentExt, Data, DataEnum, DeriveInput, Expr, Field, Fields, GenericParam, Ident, Type};
+
+use super::display::unit_variant_display_str;
+use crate::util::{
+    attribute_parsing::{as_key_value, get_attribute_args, GetAttribute},
+    type_parsing::{get_json_shape, is_option, JsonShape},
+};
+
+const JSON_SCHEMA_DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/// How [type_schema] describes the types it can't work out from the type alone.
+pub(crate) struct SchemaContext<'a> {
+    /// The type being described. A reference back to it (e.g. `children: Vec<Self>`) is described by `recursive`,
+    /// instead of nesting forever.
+    pub ident: &'a Ident,
+    pub recursive: TokenStream,
+    /// Whether a nested type that doesn't derive `JsonSchema` (e.g. a foreign type like `Ulid`) accepts anything,
+    /// instead of failing to compile.
+    pub allow_unknown: bool,
+}
+
+/// Builds the tokens for the schema of a type, e.g. `{ "type": "array", "items": { "type": "string" } }` for
+/// `Vec<String>`. Nested types are expected to derive `JsonSchema` themselves, unless the context allows unknown
+/// types.
+pub(crate) fn type_schema(
+    ty: &Type,
+    context: &SchemaContext,
+) -> TokenStream {
+    match get_json_shape(ty) {
+        JsonShape::Primitive(schema_type, Some(format)) => {
+            quote!(serde_json::json!({ "type": #schema_type, "format": #format }))
+        },
+        JsonShape::Primitive(schema_type, None) => quote!(serde_json::json!({ "type": #schema_type })),
+        JsonShape::Optional(inner) => {
+            let inner = type_schema(inner, context);
+            quote!({
+                let inner = #inner;
+                serde_json::json!({ "anyOf": [inner, { "type": "null" }] })
+            })
+        },
+        JsonShape::Array {
+            items,
+            unique,
+        } => {
+            let items = type_schema(items, context);
+            let unique = unique.then(|| quote!("uniqueItems": true,));
+            quote!({
+                let items = #items;
+                serde_json::json!({ "type": "array", #unique "items": items })
+            })
+        },
+        JsonShape::Map(value) => {
+            let value = type_schema(value, context);
+            quote!({
+                let value = #value;
+                serde_json::json!({ "type": "object", "additionalProperties": value })
+            })
+        },
+        JsonShape::Any => quote!(serde_json::json!({})),
+        JsonShape::Nested(ty) => {
+            let is_recursive = match ty {
+                Type::Path(typepath) => typepath
+                    .path
+                    .segments
+                    .last()
+                    .is_some_and(|segment| segment.ident == *context.ident || segment.ident == "Self"),
+                _ => false,
+            };
+            let schema = match (is_recursive, context.allow_unknown) {
+                (true, _) => return context.recursive.clone(),
+                // The derived `json_schema()` is an inherent fn, which takes precedence over this fallback.
+                (false, true) => quote!({
+                    #[allow(dead_code)]
+                    trait UnknownJsonSchema {
+                        fn json_schema() -> serde_json::Value {
+                            serde_json::json!({})
+                        }
+                    }
+                    impl<T: ?Sized> UnknownJsonSchema for T {}
+                    <#ty>::json_schema()
+                }),
+                (false, false) => quote!(<#ty>::json_schema()),
+            };
+            quote!({
+                let mut schema = #schema;
+                if let Some(schema) = schema.as_object_mut() {
+                    schema.remove("$schema");
+                }
+                schema
+            })
+        },
+    }
+}
+
+/// Builds the schema for a field: from its `#[json_schema(with = path)]` override, where `path` is a
+/// `fn() -> serde_json::Value`, or else from its type.
+pub(crate) fn field_schema(
+    field: &Field,
+    context: &SchemaContext,
+) -> syn::Result<TokenStream> {
+    let Some(attr) = field.get_attribute("json_schema") else {
+        return Ok(type_schema(&field.ty, context));
+    };
+    match get_attribute_args(attr)?.as_slice() {
+        [arg] => match as_key_value(arg) {
+            Some((key, Expr::Path(path))) if key == "with" => Ok(quote!(#path())),
+            _ => Err(syn::Error::new_spanned(arg, "Expected `#[json_schema(with = path::to_fn)]`")),
+        },
+        _ => Err(syn::Error::new_spanned(attr, "Expected `#[json_schema(with = path::to_fn)]`")),
+    }
+}
+
+/// Builds an object schema from named fields. Every field is required except for `Option`s, unless `all_optional`
+/// is set (e.g. for partial updates).
+pub(crate) fn object_schema(
+    fields: &[&Field],
+    all_optional: bool,
+    context: &SchemaContext,
+) -> syn::Result<TokenStream> {
+    let names: Vec<String> =
+        fields.iter().filter_map(|f| f.ident.as_ref()).map(|ident| ident.unraw().to_string()).collect();
+    let schemas = fields.iter().map(|f| field_schema(f, context)).collect::<syn::Result<Vec<_>>>()?;
+    let required = fields
+        .iter()
+        .filter(|f| !all_optional && !is_option(f))
+        .filter_map(|f| f.ident.as_ref())
+        .map(|ident| ident.unraw().to_string());
+    Ok(quote!({
+        let mut properties = serde_json::Map::new();
+        #(properties.insert(#names.to_string(), #schemas);)*
+        serde_json::json!({
+            "type": "object",
+            "properties": properties,
+            "required": serde_json::Value::from(Vec::<&str>::from([#(#required),*])),
+        })
+    }))
+}
+
+fn struct_schema(
+    ident: &Ident,
+    fields: &Fields,
+) -> syn::Result<TokenStream> {
+    let context = SchemaContext {
+        ident,
+        // A recursive type refers back to the root of its own document.
+        recursive: quote!(serde_json::json!({ "$ref": "#" })),
+        allow_unknown: false,
+    };
+    match fields {
+        Fields::Named(fields) => object_schema(&fields.named.iter().collect::<Vec<_>>(), false, &context),
+        // Newtypes serialize as their inner value.
+        Fields::Unnamed(fields) if fields.unnamed.len() == 1 => field_schema(&fields.unnamed[0], &context),
+        Fields::Unnamed(fields) => {
+            let schemas =
+                fields.unnamed.iter().map(|f| field_schema(f, &context)).collect::<syn::Result<Vec<_>>>()?;
+            let len = fields.unnamed.len();
+            Ok(quote!({
+                let prefix_items: Vec<serde_json::Value> = vec![#(#schemas),*];
+                serde_json::json!({
+                    "type": "array",
+                    "prefixItems": prefix_items,
+                    "minItems": #len,
+                    "maxItems": #len,
+                })
+            }))
+        },
+        Fields::Unit => Ok(quote!(serde_json::json!({ "type": "null" }))),
+    }
+}
+
+/// Enums are described by the names their derived `Display` / `FromStr` use, e.g. `#[display(rename_all = "...")]`.
+fn enum_schema(
+    input: &DeriveInput,
+    data: &DataEnum,
+) -> syn::Result<TokenStream> {
+    let mut names = Vec::new();
+    let mut missing_patterns = Vec::new();
+    for variant in &data.variants {
+        match &variant.fields {
+            Fields::Unit => names.push(unit_variant_display_str(input, variant)?),
+            _ if variant.get_attribute("from_str").is_some() => {},
+            _ => missing_patterns.push(format!("`{}`", variant.ident)),
+        }
+    }
+    if !missing_patterns.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            format!(
+                "Variants with data need a #[from_str(\"...\")] pattern to be described by a name. Missing a pattern: {}",
+                missing_patterns.join(", ")
+            ),
+        ));
+    }
+    // Variants parsed from a pattern can be any string, so only the unit variants can be listed.
+    match names.len() == data.variants.len() {
+        true => Ok(quote!(serde_json::json!({ "type": "string", "enum": [#(#names),*] }))),
+        false => Ok(quote!(serde_json::json!({ "type": "string", "examples": [#(#names),*] }))),
+    }
+}
+
+fn build_json_schema(input: &DeriveInput) -> syn::Result<TokenStream> {
+    let &DeriveInput {
+        ident,
+        data,
+        generics,
+        ..
+    } = &input;
+    if let Some(param) = generics.params.iter().find(|param| !matches!(param, GenericParam::Lifetime(_))) {
+        return Err(syn::Error::new_spanned(param, "JsonSchema can't be derived for types with generic parameters"));
+    }
+    let schema = match data {
+        Data::Struct(data) => struct_schema(ident, &data.fields)?,
+        Data::Enum(data) => enum_schema(input, data)?,
+        Data::Union(data) => {
+            return Err(syn::Error::new(data.union_token.span, "JsonSchema can't be derived for unions"))
+        },
+    };
+
+    let title = ident.to_string();
+    let doc = format!("The JSON Schema describing a serialized [`{}`].", ident);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    Ok(quote!(
+        impl #impl_generics #ident #ty_generics #where_clause {
+            #[doc = #doc]
+            pub fn json_schema() -> serde_json::Value {
+                let mut schema = #schema;
+                if let Some(object) = schema.as_object_mut() {
+                    object.insert("$schema".to_string(), serde_json::Value::from(#JSON_SCHEMA_DIALECT));
+                    object.insert("title".to_string(), serde_json::Value::from(#title));
+                }
+                schema
+            }
+        }
+    ))
+}
+
+/// Logic for deriving JsonSchema - generates `fn json_schema() -> serde_json::Value`, a JSON Schema document
+/// describing the type.
+///
+/// Behavior:
+///  - Structs with named fields are objects. Every field is required, except for `Option`s, which may also be `null`.
+///  - Newtypes are described by their inner type, and tuple structs are fixed-length arrays.
+///  - Primitives, `Uuid` and `chrono` dates map to their JSON types, `Vec` / `HashSet` to arrays, and
+///    `HashMap<K, V>` to objects of `V`.
+///  - Any other type is nested, and must derive `JsonSchema` as well. Foreign types (e.g. `Ulid`) can be described
+///    with `#[json_schema(with = path::to_fn)]` on the field instead, where the fn returns the `serde_json::Value`.
+///  - Enums are strings, listing the names of their unit variants as written by the derived `Display` / `FromStr`.
+///    Variants with data need a `#[from_str("...")]` pattern, and make the enum any string.
+pub fn derive_json_schema(input: &DeriveInput) -> TokenStream {
+    build_json_schema(input).unwrap_or_else(syn::Error::into_compile_error)
+}
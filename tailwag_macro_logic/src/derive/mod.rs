@@ -3,6 +3,10 @@ pub mod gui;
 
 mod util_traits;
 
+pub mod builder;
+
+pub mod json_schema;
+
 pub mod forms;
 mod web_service;
 pub use web_service::*;
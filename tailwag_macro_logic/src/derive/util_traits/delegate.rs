@@ -0,0 +1,180 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{parse_quote, DeriveInput, GenericParam, Generics, WherePredicate};
+
+use super::deref::find_tagged_field;
+use crate::util::attribute_parsing::{as_flag, get_attribute_args, GetAttribute};
+
+const SUPPORTED_TRAITS: &str = "IntoIterator, Index, IndexMut, AsRef, AsMut, Extend";
+
+/// Adds a generic parameter (and optionally a where clause predicate) to the struct's generics, for impls like
+/// `impl<'a> IntoIterator for &'a Wrapper` that need more parameters than the struct itself declares.
+fn extend_generics(
+    generics: &Generics,
+    param: Option<GenericParam>,
+    predicate: WherePredicate,
+) -> Generics {
+    let mut generics = generics.clone();
+    if let Some(param) = param {
+        generics.params.push(param);
+    }
+    generics.make_where_clause().predicates.push(predicate);
+    generics
+}
+
+fn build_delegate(input: &DeriveInput) -> syn::Result<TokenStream> {
+    let &DeriveInput {
+        ident,
+        generics,
+        ..
+    } = &input;
+    let Some(attr) = input.get_attribute("delegate") else {
+        return Err(syn::Error::new_spanned(
+            ident,
+            format!("Missing the traits to delegate, e.g. #[delegate(IntoIterator, Index)]. Supported: {}", SUPPORTED_TRAITS),
+        ));
+    };
+    let Some((member, field)) = find_tagged_field(input, "delegate", "Delegate")? else {
+        return Err(syn::Error::new_spanned(ident, "Delegate can't be derived for a struct with no fields"));
+    };
+    let field_type = &field.ty;
+    let (_, ty_generics, _) = generics.split_for_impl();
+
+    let mut impls = Vec::new();
+    for arg in get_attribute_args(attr)? {
+        let Some(trait_name) = as_flag(&arg) else {
+            return Err(syn::Error::new_spanned(arg, format!("Expected a trait name. Supported: {}", SUPPORTED_TRAITS)));
+        };
+        let tokens = match trait_name.to_string().as_str() {
+            "IntoIterator" => {
+                let owned = extend_generics(generics, None, parse_quote!(#field_type: ::std::iter::IntoIterator));
+                let (impl_generics, _, where_clause) = owned.split_for_impl();
+                let by_ref = extend_generics(
+                    generics,
+                    Some(parse_quote!('__delegate)),
+                    parse_quote!(&'__delegate #field_type: ::std::iter::IntoIterator),
+                );
+                let (ref_impl_generics, _, ref_where_clause) = by_ref.split_for_impl();
+                let by_mut = extend_generics(
+                    generics,
+                    Some(parse_quote!('__delegate)),
+                    parse_quote!(&'__delegate mut #field_type: ::std::iter::IntoIterator),
+                );
+                let (mut_impl_generics, _, mut_where_clause) = by_mut.split_for_impl();
+                quote!(
+                    impl #impl_generics ::std::iter::IntoIterator for #ident #ty_generics #where_clause {
+                        type Item = <#field_type as ::std::iter::IntoIterator>::Item;
+                        type IntoIter = <#field_type as ::std::iter::IntoIterator>::IntoIter;
+                        fn into_iter(self) -> Self::IntoIter {
+                            ::std::iter::IntoIterator::into_iter(self.#member)
+                        }
+                    }
+                    impl #ref_impl_generics ::std::iter::IntoIterator for &'__delegate #ident #ty_generics #ref_where_clause {
+                        type Item = <&'__delegate #field_type as ::std::iter::IntoIterator>::Item;
+                        type IntoIter = <&'__delegate #field_type as ::std::iter::IntoIterator>::IntoIter;
+                        fn into_iter(self) -> Self::IntoIter {
+                            ::std::iter::IntoIterator::into_iter(&self.#member)
+                        }
+                    }
+                    impl #mut_impl_generics ::std::iter::IntoIterator for &'__delegate mut #ident #ty_generics #mut_where_clause {
+                        type Item = <&'__delegate mut #field_type as ::std::iter::IntoIterator>::Item;
+                        type IntoIter = <&'__delegate mut #field_type as ::std::iter::IntoIterator>::IntoIter;
+                        fn into_iter(self) -> Self::IntoIter {
+                            ::std::iter::IntoIterator::into_iter(&mut self.#member)
+                        }
+                    }
+                )
+            },
+            "Index" => {
+                let extended = extend_generics(
+                    generics,
+                    Some(parse_quote!(__Idx)),
+                    parse_quote!(#field_type: ::std::ops::Index<__Idx>),
+                );
+                let (impl_generics, _, where_clause) = extended.split_for_impl();
+                quote!(
+                    impl #impl_generics ::std::ops::Index<__Idx> for #ident #ty_generics #where_clause {
+                        type Output = <#field_type as ::std::ops::Index<__Idx>>::Output;
+                        fn index(&self, index: __Idx) -> &Self::Output {
+                            ::std::ops::Index::index(&self.#member, index)
+                        }
+                    }
+                )
+            },
+            "IndexMut" => {
+                let extended = extend_generics(
+                    generics,
+                    Some(parse_quote!(__Idx)),
+                    parse_quote!(#field_type: ::std::ops::IndexMut<__Idx>),
+                );
+                let (impl_generics, _, where_clause) = extended.split_for_impl();
+                quote!(
+                    impl #impl_generics ::std::ops::IndexMut<__Idx> for #ident #ty_generics #where_clause {
+                        fn index_mut(&mut self, index: __Idx) -> &mut Self::Output {
+                            ::std::ops::IndexMut::index_mut(&mut self.#member, index)
+                        }
+                    }
+                )
+            },
+            "AsRef" => {
+                let (impl_generics, _, where_clause) = generics.split_for_impl();
+                quote!(
+                    impl #impl_generics ::std::convert::AsRef<#field_type> for #ident #ty_generics #where_clause {
+                        fn as_ref(&self) -> &#field_type {
+                            &self.#member
+                        }
+                    }
+                )
+            },
+            "AsMut" => {
+                let (impl_generics, _, where_clause) = generics.split_for_impl();
+                quote!(
+                    impl #impl_generics ::std::convert::AsMut<#field_type> for #ident #ty_generics #where_clause {
+                        fn as_mut(&mut self) -> &mut #field_type {
+                            &mut self.#member
+                        }
+                    }
+                )
+            },
+            "Extend" => {
+                let extended = extend_generics(
+                    generics,
+                    Some(parse_quote!(__Item)),
+                    parse_quote!(#field_type: ::std::iter::Extend<__Item>),
+                );
+                let (impl_generics, _, where_clause) = extended.split_for_impl();
+                quote!(
+                    impl #impl_generics ::std::iter::Extend<__Item> for #ident #ty_generics #where_clause {
+                        fn extend<__Iter: ::std::iter::IntoIterator<Item = __Item>>(&mut self, iter: __Iter) {
+                            ::std::iter::Extend::extend(&mut self.#member, iter)
+                        }
+                    }
+                )
+            },
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    trait_name,
+                    format!("Delegating {} is not supported. Supported: {}", trait_name, SUPPORTED_TRAITS),
+                ))
+            },
+        };
+        impls.push(tokens);
+    }
+
+    Ok(quote!(#(#impls)*))
+}
+
+/// Logic for deriving Delegate - forwards the listed traits to the struct's inner field.
+///
+/// The traits are listed on the struct, e.g. `#[delegate(IntoIterator, Index, AsRef)]`, and are implemented against
+/// the field tagged with `#[delegate]` (or the only field in the struct), the same way `Deref` picks its target.
+/// Inherent methods like `len` / `is_empty` / `iter` aren't traits, so reach for `Deref` to expose those instead.
+///
+/// Supported traits:
+///  - `IntoIterator`, for `Self`, `&Self` and `&mut Self`
+///  - `Index` / `IndexMut`, for any index type the field supports
+///  - `AsRef` / `AsMut`, to the field's type
+///  - `Extend`, for any item type the field can be extended with
+pub fn derive_delegate(input: &DeriveInput) -> TokenStream {
+    build_delegate(input).unwrap_or_else(syn::Error::into_compile_error)
+}
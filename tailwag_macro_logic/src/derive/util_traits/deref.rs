@@ -1,49 +1,199 @@
 use proc_macro2::TokenStream;
 use quote::quote;
-use syn::{Data, DeriveInput};
+use syn::{Attribute, Data, DeriveInput, Field, Member, Type};
 
-use crate::util::attribute_parsing::GetAttribute;
+use crate::util::attribute_parsing::{as_flag, as_key_value, as_lit_str, get_attribute_args, GetAttribute};
 
-/// Logic for deriving Deref - only tested with structs using named fields.
+/// Options parsed from the `#[deref(...)]` attribute on the target field.
+#[derive(Default)]
+struct DerefOptions {
+    /// `#[deref(target = "str")]` - deref to a type the field coerces to, e.g. `String` -> `str` or `Vec<T>` -> `[T]`.
+    target: Option<Type>,
+    /// `#[deref(forward)]` - deref through the field's own `Deref`, to its `Target`.
+    forward: bool,
+    /// `#[deref(as_ref)]` - also implement `AsRef<Target>`.
+    as_ref: bool,
+    /// `#[deref(borrow)]` - also implement `Borrow<Target>`.
+    borrow: bool,
+}
+
+impl DerefOptions {
+    fn from_attribute(attr: Option<&Attribute>) -> syn::Result<Self> {
+        let mut options = Self::default();
+        let Some(attr) = attr else {
+            return Ok(options);
+        };
+        for arg in get_attribute_args(attr)? {
+            match (as_flag(&arg), as_key_value(&arg)) {
+                (Some(flag), _) if flag == "forward" => options.forward = true,
+                (Some(flag), _) if flag == "as_ref" => options.as_ref = true,
+                (Some(flag), _) if flag == "borrow" => options.borrow = true,
+                (_, Some((key, value))) if key == "target" => {
+                    let target = as_lit_str(value)
+                        .ok_or_else(|| syn::Error::new_spanned(value, "Expected a type as a string, e.g. \"str\""))?;
+                    options.target = Some(target.parse()?);
+                },
+                _ => return Err(syn::Error::new_spanned(arg, "Unrecognized deref option")),
+            }
+        }
+        if options.forward && options.target.is_some() {
+            return Err(syn::Error::new_spanned(attr, "`forward` and `target` can't be used together"));
+        }
+        Ok(options)
+    }
+}
+
+/// The field a struct derefs to, along with the options from its `#[deref(...)]` attribute.
+struct DerefTarget<'a> {
+    /// The member used to access the field: `self.name` for named fields, `self.0` for tuple structs.
+    member: Member,
+    field: &'a Field,
+    options: DerefOptions,
+}
+
+impl DerefTarget<'_> {
+    fn target_type(&self) -> TokenStream {
+        let field_type = &self.field.ty;
+        match (&self.options.target, self.options.forward) {
+            (Some(target), _) => quote!(#target),
+            (None, true) => quote!(<#field_type as ::std::ops::Deref>::Target),
+            (None, false) => quote!(#field_type),
+        }
+    }
+
+    fn deref_tokens(&self) -> TokenStream {
+        let member = &self.member;
+        match self.options.forward {
+            true => quote!(::std::ops::Deref::deref(&self.#member)),
+            false => quote!(&self.#member),
+        }
+    }
+
+    fn deref_mut_tokens(&self) -> TokenStream {
+        let member = &self.member;
+        match self.options.forward {
+            true => quote!(::std::ops::DerefMut::deref_mut(&mut self.#member)),
+            false => quote!(&mut self.#member),
+        }
+    }
+}
+
+/// Finds the field tagged with `#[attr_name]`, or the only field of the struct if none are tagged.
+///
+/// Returns the member used to access it (`self.name` for named fields, `self.0` for tuple structs) along with the
+/// field. Errors (spanned at the offending item) if the input isn't a struct, if more than one field is tagged, or if
+/// there are multiple fields and none are tagged. Fieldless structs return `None`.
+pub(crate) fn find_tagged_field<'a>(
+    input: &'a DeriveInput,
+    attr_name: &str,
+    trait_name: &str,
+) -> syn::Result<Option<(Member, &'a Field)>> {
+    let data = match &input.data {
+        Data::Struct(data) => data,
+        Data::Enum(data) => {
+            return Err(syn::Error::new(
+                data.enum_token.span,
+                format!("{} can only be derived for structs, not enums", trait_name),
+            ))
+        },
+        Data::Union(data) => {
+            return Err(syn::Error::new(
+                data.union_token.span,
+                format!("{} can only be derived for structs, not unions", trait_name),
+            ))
+        },
+    };
+
+    let mut fields = data.fields.members().zip(data.fields.iter());
+    let mut tagged = fields.clone().filter(|(_, f)| f.get_attribute(attr_name).is_some());
+    match tagged.next() {
+        Some(target) => {
+            let duplicates = tagged.filter_map(|(_, f)| f.get_attribute(attr_name)).map(|attr| {
+                syn::Error::new_spanned(attr, format!("Only one field can be tagged with #[{}]", attr_name))
+            });
+            match duplicates.reduce(|mut acc, err| {
+                acc.combine(err);
+                acc
+            }) {
+                Some(err) => Err(err),
+                None => Ok(Some(target)),
+            }
+        },
+        None if data.fields.len() <= 1 => Ok(fields.next()),
+        None => Err(syn::Error::new_spanned(
+            &input.ident,
+            format!(
+                "More than one field found in struct. Use #[{0}] to tag the field you want to {0}.",
+                attr_name
+            ),
+        )),
+    }
+}
+
+/// Finds the field to deref to: the one tagged with `#[deref]`, or the only field of the struct.
+fn find_deref_target(input: &DeriveInput) -> syn::Result<Option<DerefTarget<'_>>> {
+    find_tagged_field(input, "deref", "Deref")?
+        .map(|(member, field)| {
+            Ok(DerefTarget {
+                member,
+                field,
+                options: DerefOptions::from_attribute(field.get_attribute("deref"))?,
+            })
+        })
+        .transpose()
+}
+
+/// Logic for deriving Deref, for structs with named fields and tuple structs / newtypes.
+///
+/// Derefs to the field tagged with the `#[deref]` attribute  - if not found, it defaults to the only field in the struct.
 ///
-/// Derefs to the field tagged with the `#[deref]` attribute  - if not found, it defaults to the first declared field in the struct.
+/// Options, on the field's attribute:
+///  - `#[deref(target = "str")]` derefs to a type the field coerces to, e.g. `String` -> `str` or `Vec<T>` -> `[T]`.
+///  - `#[deref(forward)]` chains through the field's own `Deref`, so `Target` is the field's `Deref::Target`.
+///  - `#[deref(as_ref)]` / `#[deref(borrow)]` also implement `AsRef<Target>` / `Borrow<Target>`.
 pub fn derive_deref(input: &DeriveInput) -> TokenStream {
     let &DeriveInput {
         ident,
         generics,
-        data,
         ..
     } = &input;
-    let Data::Struct(data) = data else {
-        panic!("Only Structs are supported")
-    };
     let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
 
-    let target = data
-        .fields
-        .iter()
-        .find(|f| f.get_attribute("deref").is_some())
-        .map_or_else(
-            || {
-                if data.fields.len() <= 1 {
-                    data.fields.iter().next()
-                } else {
-                    panic!("More than one field found in struct. Use #[deref] to tag the field you want to deref.")
-                }
-            },
-            |f| Some(f),
-        )
-        .map(|field| {
-            let target_name = &field.ident;
-            let target_type = &field.ty;
-            (quote!(&self.#target_name), quote!(#target_type))
-        });
+    let target = match find_deref_target(input) {
+        Ok(target) => target,
+        Err(err) => return err.into_compile_error(),
+    };
 
     let ident_string = ident.to_string();
     // If it's a fieldless struct, we simply deref the name of the struct.
-    let (target_ident, target_type) = target.unwrap_or((quote!(#ident_string), quote!(str)));
+    let (target_ident, target_type) = target
+        .as_ref()
+        .map(|target| (target.deref_tokens(), target.target_type()))
+        .unwrap_or((quote!(#ident_string), quote!(str)));
+
+    let as_ref_tokens = target.as_ref().filter(|target| target.options.as_ref).map(|_| {
+        quote!(
+            impl #impl_generics ::std::convert::AsRef<#target_type> for #ident #ty_generics
+                #where_clause
+            {
+                fn as_ref(&self) -> &#target_type {
+                    ::std::ops::Deref::deref(self)
+                }
+            }
+        )
+    });
+    let borrow_tokens = target.as_ref().filter(|target| target.options.borrow).map(|_| {
+        quote!(
+            impl #impl_generics ::std::borrow::Borrow<#target_type> for #ident #ty_generics
+                #where_clause
+            {
+                fn borrow(&self) -> &#target_type {
+                    ::std::ops::Deref::deref(self)
+                }
+            }
+        )
+    });
 
-    // TODO: Time for generics!
     let tokens = quote!(
         impl #impl_generics std::ops::Deref for #ident #ty_generics
             #where_clause
@@ -53,56 +203,44 @@ pub fn derive_deref(input: &DeriveInput) -> TokenStream {
                 #target_ident
             }
         }
+        #as_ref_tokens
+        #borrow_tokens
     );
     tokens.into()
 }
 
-/// Logic for deriving Deref - only tested with structs using named fields.
+/// Logic for deriving DerefMut, for structs with named fields and tuple structs / newtypes.
 ///
-/// Derefs to the field tagged with the `#[deref]` attribute  - if not found, it defaults to the first declared field in the struct.
+/// Derefs to the field tagged with the `#[deref]` attribute  - if not found, it defaults to the only field in the struct.
+/// Honors the same `target` / `forward` options as [derive_deref]. Unlike `Deref`, fieldless structs are not supported.
 pub fn derive_deref_mut(input: &DeriveInput) -> TokenStream {
     let &DeriveInput {
         ident,
         generics,
-        data,
         ..
     } = &input;
-    let Data::Struct(data) = data else {
-        panic!("Only Structs are supported")
-    };
     let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
 
-    let target = data
-        .fields
-        .iter()
-        .find(|f| f.get_attribute("deref").is_some())
-        .map_or_else(
-            || {
-                if data.fields.len() <= 1 {
-                    data.fields.iter().next()
-                } else {
-                    panic!("More than one field found in struct. Use #[deref] to tag the field you want to deref.")
-                }
-            },
-            |f| Some(f),
-        )
-        .map(|field| {
-            let target_name = &field.ident;
-            let target_type = &field.ty;
-            (quote!(&self.#target_name), quote!(#target_type))
-        });
-
-    let ident_string = ident.to_string();
-    // If it's a fieldless struct, we simply deref the name of the struct.
-    let (target_ident, _target_type) = target.unwrap_or((quote!(#ident_string), quote!(str)));
+    // Fieldless structs deref to their name, which is a `&'static str` and can't be borrowed mutably.
+    let target = match find_deref_target(input) {
+        Ok(Some(target)) => target,
+        Ok(None) => {
+            return syn::Error::new_spanned(
+                ident,
+                "DerefMut can't be derived for a struct with no fields. Add a field to deref to, or derive only Deref.",
+            )
+            .into_compile_error()
+        },
+        Err(err) => return err.into_compile_error(),
+    };
+    let target_ident = target.deref_mut_tokens();
 
-    // TODO: Time for generics!
     let tokens = quote!(
         impl #impl_generics std::ops::DerefMut for #ident #ty_generics
             #where_clause
         {
             fn deref_mut(&mut self) -> &mut Self::Target {
-                &mut #target_ident
+                #target_ident
             }
         }
     );
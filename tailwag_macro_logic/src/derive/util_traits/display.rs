@@ -1,35 +1,327 @@
-use proc_macro2::TokenStream;
-use quote::quote;
-use syn::{Data, DeriveInput};
-use tailwag_utils::macro_utils::attribute_parsing::GetAttribute;
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote};
+use syn::{ext::IdentExt, Attribute, Data, DeriveInput, Fields, Ident, LitStr, Variant};
 
-/// Logic for deriving Deref - only tested with structs using named fields.
+use super::from_string::variant_pattern;
+use crate::util::{
+    attribute_parsing::{as_flag, as_key_value, as_lit_str, get_attribute_args, GetAttribute},
+    rename_rule::RenameRule,
+};
+
+/// Options parsed from a `#[display(...)]` attribute on a struct, an enum, or one of its variants.
+#[derive(Default)]
+struct DisplayOptions {
+    /// `#[display(payload)]` - write the variant's fields after its name, e.g. `Failed { reason: timeout }`.
+    payload: bool,
+    /// `#[display("{name} <{email}>")]` - a format string, interpolating the fields by name (or `{0}` for tuples).
+    format: Option<LitStr>,
+    /// `#[display(rename = "...")]` - the name written for a variant, in place of its identifier.
+    rename: Option<String>,
+    /// `#[display(rename_all = "snake_case")]` - the casing rule applied to every variant name of an enum.
+    rename_all: Option<RenameRule>,
+}
+
+impl DisplayOptions {
+    fn from_attribute(attr: Option<&Attribute>) -> syn::Result<Self> {
+        let mut options = Self::default();
+        let Some(attr) = attr else {
+            return Ok(options);
+        };
+        for arg in get_attribute_args(attr)? {
+            if let Some(format) = as_lit_str(&arg) {
+                options.format = Some(format.clone());
+                continue;
+            }
+            match (as_flag(&arg), as_key_value(&arg)) {
+                (Some(flag), _) if flag == "payload" => options.payload = true,
+                (_, Some((key, value))) if key == "rename" => {
+                    let rename = as_lit_str(value)
+                        .ok_or_else(|| syn::Error::new_spanned(value, "Expected a string literal"))?;
+                    options.rename = Some(rename.value());
+                },
+                (_, Some((key, value))) if key == "rename_all" => {
+                    let rule = as_lit_str(value)
+                        .ok_or_else(|| syn::Error::new_spanned(value, "Expected a string literal"))?;
+                    options.rename_all = Some(RenameRule::parse(rule)?);
+                },
+                _ => return Err(syn::Error::new_spanned(arg, "Unrecognized display option")),
+            }
+        }
+        Ok(options)
+    }
+
+    /// The name written for a variant: its `rename`, or its identifier with the enum's `rename_all` rule applied.
+    fn variant_name(
+        &self,
+        variant: &Variant,
+        enum_options: &DisplayOptions,
+    ) -> String {
+        let ident = variant.ident.to_string();
+        self.rename.clone().unwrap_or_else(|| match enum_options.rename_all {
+            Some(rule) => rule.apply(&ident),
+            None => ident,
+        })
+    }
+}
+
+/// The format string a variant is written with: its `#[display("...")]`, or else the `#[from_str("...")]` pattern it's
+/// parsed from, so that the Display and FromStr derives round-trip. Errors if the two are both given and disagree.
+fn variant_format(
+    variant: &Variant,
+    options: &DisplayOptions,
+) -> syn::Result<Option<LitStr>> {
+    match (&options.format, variant_pattern(variant)?) {
+        (Some(format), Some(pattern)) if format.value() != pattern.value() => Err(syn::Error::new(
+            format.span(),
+            format!(
+                "#[display(\"{}\")] doesn't match #[from_str(\"{}\")], so `{}` wouldn't parse back from its Display output. Use the same string for both, or drop the #[display]",
+                format.value(),
+                pattern.value(),
+                variant.ident
+            ),
+        )),
+        (Some(format), _) => Ok(Some(format.clone())),
+        (None, pattern) => Ok(pattern),
+    }
+}
+
+/// Escapes any braces in a literal, so it can be used as part of a format string.
+fn escape_braces(literal: &str) -> String {
+    literal.replace('{', "{{").replace('}', "}}")
+}
+
+/// A `#[display("...")]` format string, rewritten so that every placeholder refers to a named argument.
 ///
-/// Derefs to the field tagged with the `#[deref]` attribute  - if not found, it defaults to the first declared field in the struct.
+/// Positional placeholders (`{0}`) are renamed to the `_0` binding used for tuple fields, since
+/// `format_args!` does not accept numeric names for named arguments.
+struct FormatString {
+    value: String,
+    /// The names of the fields referenced by the format string, in order of first use.
+    args: Vec<String>,
+}
+
+impl FormatString {
+    fn parse(
+        format: &str,
+        span: Span,
+    ) -> syn::Result<Self> {
+        let mut value = String::new();
+        let mut args: Vec<String> = Vec::new();
+        let mut chars = format.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    value.push_str("{{");
+                },
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    value.push_str("}}");
+                },
+                '{' => {
+                    let mut placeholder = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(c) => placeholder.push(c),
+                            None => return Err(syn::Error::new(span, "Unclosed `{` in format string")),
+                        }
+                    }
+                    let (arg, spec) = match placeholder.split_once(':') {
+                        Some((arg, spec)) => (arg.trim(), Some(spec)),
+                        None => (placeholder.trim(), None),
+                    };
+                    // `{r#type}` and `{type}` both refer to the `r#type` field.
+                    let arg = arg.strip_prefix("r#").unwrap_or(arg);
+                    if arg.is_empty() {
+                        return Err(syn::Error::new(
+                            span,
+                            "Unnamed `{}` placeholders are not supported. Name the field instead, e.g. `{name}` or `{0}`",
+                        ));
+                    }
+                    let arg = match arg.chars().all(|c| c.is_ascii_digit()) {
+                        true => format!("_{}", arg),
+                        false => arg.to_string(),
+                    };
+                    value.push('{');
+                    value.push_str(&arg);
+                    if let Some(spec) = spec {
+                        value.push(':');
+                        value.push_str(spec);
+                    }
+                    value.push('}');
+                    if !args.contains(&arg) {
+                        args.push(arg);
+                    }
+                },
+                '}' => return Err(syn::Error::new(span, "Unmatched `}` in format string")),
+                c => value.push(c),
+            }
+        }
+        Ok(Self {
+            value,
+            args,
+        })
+    }
+}
+
+/// Builds the pattern that destructures `path` (e.g. `Self` or `Self::Variant`), binding only the fields
+/// referenced by `format`, along with the `write!` statement that formats them.
+fn build_format_tokens(
+    path: TokenStream,
+    fields: &Fields,
+    format: &str,
+    span: Span,
+) -> syn::Result<(TokenStream, TokenStream)> {
+    let format = FormatString::parse(format, span)?;
+    // Named fields are found by their own identifier, so raw identifiers like `r#type` can be used as the argument's
+    // name.
+    let find_field = |arg: &String| match fields {
+        Fields::Named(fields) => fields.named.iter().filter_map(|f| f.ident.clone()).find(|ident| ident.unraw() == arg),
+        Fields::Unnamed(fields) => (0..fields.unnamed.len()).map(|i| format_ident!("_{}", i, span = span)).find(|p| p == arg),
+        Fields::Unit => None,
+    };
+    let names = format
+        .args
+        .iter()
+        .map(|arg| {
+            find_field(arg)
+                .ok_or_else(|| syn::Error::new(span, format!("No field `{}` found", arg.trim_start_matches('_'))))
+        })
+        .collect::<syn::Result<Vec<Ident>>>()?;
+    // Named fields are bound under a prefix, so that a field named `f` doesn't shadow the formatter.
+    let bindings: Vec<Ident> = match fields {
+        Fields::Named(_) => names.iter().map(|name| format_ident!("__display_{}", name.unraw())).collect(),
+        _ => names.clone(),
+    };
+
+    let pattern = match fields {
+        Fields::Named(_) => quote!(#path { #(#names: #bindings,)* .. }),
+        Fields::Unnamed(fields) => {
+            let patterns = (0..fields.unnamed.len()).map(|i| format_ident!("_{}", i)).map(|p| {
+                match format.args.contains(&p.to_string()) {
+                    true => quote!(#p),
+                    false => quote!(_),
+                }
+            });
+            quote!(#path(#(#patterns),*))
+        },
+        Fields::Unit => quote!(#path),
+    };
+
+    let format_str = format.value;
+    Ok((pattern, quote!(write!(f, #format_str, #(#names = #bindings),*))))
+}
+
+fn build_variant_arm(
+    variant: &Variant,
+    enum_options: &DisplayOptions,
+) -> syn::Result<TokenStream> {
+    let variant_ident = &variant.ident;
+    let options = DisplayOptions::from_attribute(variant.get_attribute("display"))?;
+    let variant_str = options.variant_name(variant, enum_options);
+
+    if let Some(format) = variant_format(variant, &options)? {
+        let (pattern, write_statement) =
+            build_format_tokens(quote!(Self::#variant_ident), &variant.fields, &format.value(), format.span())?;
+        return Ok(quote!(#pattern => #write_statement));
+    }
+
+    if !(options.payload || enum_options.payload) {
+        let tokens = match &variant.fields {
+            Fields::Named(_) => quote!(Self::#variant_ident { .. } => f.write_str(#variant_str)),
+            Fields::Unnamed(_) => quote!(Self::#variant_ident(..) => f.write_str(#variant_str)),
+            Fields::Unit => quote!(Self::#variant_ident => f.write_str(#variant_str)),
+        };
+        return Ok(tokens);
+    }
+
+    let name = escape_braces(&variant_str);
+    let format = match &variant.fields {
+        Fields::Named(fields) => {
+            let placeholders = fields
+                .named
+                .iter()
+                .filter_map(|f| f.ident.as_ref())
+                .map(|ident| format!("{0}: {{{0}}}", ident.unraw()))
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!("{} {{{{ {} }}}}", name, placeholders)
+        },
+        Fields::Unnamed(fields) => {
+            let placeholders =
+                (0..fields.unnamed.len()).map(|i| format!("{{{}}}", i)).collect::<Vec<_>>().join(", ");
+            format!("{}({})", name, placeholders)
+        },
+        Fields::Unit => name,
+    };
+    let (pattern, write_statement) =
+        build_format_tokens(quote!(Self::#variant_ident), &variant.fields, &format, variant_ident.span())?;
+    Ok(quote!(#pattern => #write_statement))
+}
+
+/// The exact string written by the derived Display for a unit variant, so that `FromStr` can parse it back.
+pub(crate) fn unit_variant_display_str(
+    input: &DeriveInput,
+    variant: &Variant,
+) -> syn::Result<String> {
+    let enum_options = DisplayOptions::from_attribute(input.get_attribute("display"))?;
+    let options = DisplayOptions::from_attribute(variant.get_attribute("display"))?;
+    match variant_format(variant, &options)? {
+        Some(format) => {
+            let parsed = FormatString::parse(&format.value(), format.span())?;
+            if let Some(arg) = parsed.args.first() {
+                return Err(syn::Error::new(format.span(), format!("No field `{}` found", arg)));
+            }
+            Ok(parsed.value.replace("{{", "{").replace("}}", "}"))
+        },
+        None => Ok(options.variant_name(variant, &enum_options)),
+    }
+}
+
+/// Logic for deriving Display.
 ///
 /// Behavior:
-///  - For Enums, it implements the name of the attribute as the Display string. Does not work on enums with data inside, only simplee enums
+///  - For Enums, it writes the name of the variant as the Display string. Variants with data only write their name,
+///    unless tagged with `#[display(payload)]` (or the whole enum is), in which case the fields' Display output is
+///    included: `Amount(12.50)` / `Failed { reason: timeout }`.
+///    Variant names can be recased for the whole enum with `#[display(rename_all = "snake_case")]`
+///    (also `"kebab-case"`, `"SCREAMING_SNAKE_CASE"`, and `"Title Case"`), or replaced on a single variant
+///    with `#[display(rename = "...")]`.
 ///  - For Structs, it will either echo the Debug output of Self (must also `impl Debug`), OR
 ///     - tag a field with `#[display]` and it will proxy that Display.
+///  - Structs and variants can instead provide a format string, e.g. `#[display("{name} <{email}>")]`, which
+///    interpolates the fields by name (or by position for tuples, e.g. `#[display("{0}-{1}")]`).
+///  - Variants parsed from a `#[from_str("range:{0}-{1}")]` pattern are written with that same pattern, so that
+///    `to_string()` / `parse()` round-trip.
 pub fn derive_display(input: &DeriveInput) -> TokenStream {
     let &DeriveInput {
         ident,
         data,
         ..
     } = &input;
+    let container_options = match DisplayOptions::from_attribute(input.get_attribute("display")) {
+        Ok(options) => options,
+        Err(err) => return err.into_compile_error(),
+    };
     match data {
         Data::Enum(data) => {
-            let variants = data.variants.iter().map(|v| {
-                if v.fields.len() > 0 {
-                    panic!("This macro currently only supports no-field Enums")
-                } else {
-                    &v.ident
-                }
-            });
-            let variants_str = variants.clone().map(|v| v.to_string());
-
-            // If it's a fieldless struct, we simply deref the name of the struct.
-            // let (target_ident, target_type) = target.unwrap_or((quote!(#ident_string), quote!(str)));
+            if let Some(format) = &container_options.format {
+                return syn::Error::new(
+                    format.span(),
+                    "Format strings are only supported on enum variants, not the enum itself",
+                )
+                .into_compile_error();
+            }
+            let variant_arms = match data
+                .variants
+                .iter()
+                .map(|v| build_variant_arm(v, &container_options))
+                .collect::<syn::Result<Vec<_>>>()
+            {
+                Ok(arms) => arms,
+                Err(err) => return err.into_compile_error(),
+            };
 
             // TODO: Time for generics!
             let tokens = quote!(
@@ -39,7 +331,7 @@ pub fn derive_display(input: &DeriveInput) -> TokenStream {
                         f: &mut std::fmt::Formatter<'_>,
                     ) -> std::fmt::Result {
                         match self {
-                            #(Self::#variants => f.write_str(#variants_str),)*
+                            #(#variant_arms,)*
                         }
                     }
                 }
@@ -47,13 +339,24 @@ pub fn derive_display(input: &DeriveInput) -> TokenStream {
             tokens.into()
         },
         Data::Struct(data) => {
-            let write_statement = data
-                .fields
-                .iter()
-                .find(|f| f.get_attribute("display").is_some())
-                .and_then(|f| f.ident.as_ref())
-                .map(|f| quote!(write!(f, "{}", &self.#f)))
-                .unwrap_or(quote!(write!(f, "{:?}", &self)));
+            let write_statement = match &container_options.format {
+                Some(format) => {
+                    match build_format_tokens(quote!(Self), &data.fields, &format.value(), format.span()) {
+                        Ok((pattern, write_statement)) => quote!(
+                            let #pattern = self;
+                            #write_statement
+                        ),
+                        Err(err) => return err.into_compile_error(),
+                    }
+                },
+                None => data
+                    .fields
+                    .iter()
+                    .find(|f| f.get_attribute("display").is_some())
+                    .and_then(|f| f.ident.as_ref())
+                    .map(|f| quote!(write!(f, "{}", &self.#f)))
+                    .unwrap_or(quote!(write!(f, "{:?}", &self))),
+            };
             quote!(
             // TODO: Derive macro this
             impl ::std::fmt::Display for #ident {
@@ -1,40 +1,488 @@
 use proc_macro2::TokenStream;
-use quote::quote;
-use syn::{Data, DeriveInput};
+use quote::{format_ident, quote};
+use syn::{
+    ext::IdentExt, Attribute, Data, DataEnum, DataStruct, DeriveInput, Expr, ExprArray, Fields, LitStr, Member, Path,
+    Type, Variant,
+};
 
-/// Logic for deriving Deref - only tested with structs using named fields.
+use super::display::unit_variant_display_str;
+use crate::util::attribute_parsing::{as_flag, as_key_value, as_lit_str, get_attribute_args, GetAttribute};
+
+/// Options parsed from a `#[from_str(...)]` attribute on an enum or one of its variants.
+#[derive(Default)]
+struct FromStrOptions {
+    /// `#[from_str(case_insensitive)]` - match the input regardless of case.
+    case_insensitive: bool,
+    /// `#[from_str(alias = "...")]` / `#[from_str(alias = ["...", "..."])]` - additional strings accepted for a variant.
+    aliases: Vec<LitStr>,
+    /// `#[from_str(format = "{a}:{b}")]` (or `#[from_str("{a}:{b}")]`) - a pattern to parse the fields of a struct
+    /// or variant from.
+    format: Option<LitStr>,
+    /// `#[from_str(validate = path::to::fn)]` - a check run on the inner value of a newtype after parsing.
+    validate: Option<Path>,
+}
+
+impl FromStrOptions {
+    fn from_attribute(attr: Option<&Attribute>) -> syn::Result<Self> {
+        let mut options = Self::default();
+        let Some(attr) = attr else {
+            return Ok(options);
+        };
+        for arg in get_attribute_args(attr)? {
+            if let Some(format) = as_lit_str(&arg) {
+                options.format = Some(format.clone());
+                continue;
+            }
+            match (as_flag(&arg), as_key_value(&arg)) {
+                (Some(flag), _) if flag == "case_insensitive" => options.case_insensitive = true,
+                (_, Some((key, value))) if key == "alias" => match value {
+                    Expr::Array(ExprArray {
+                        elems,
+                        ..
+                    }) => {
+                        for elem in elems {
+                            let alias = as_lit_str(elem)
+                                .ok_or_else(|| syn::Error::new_spanned(elem, "Expected a string literal"))?;
+                            options.aliases.push(alias.clone());
+                        }
+                    },
+                    _ => {
+                        let alias = as_lit_str(value)
+                            .ok_or_else(|| syn::Error::new_spanned(value, "Expected a string literal"))?;
+                        options.aliases.push(alias.clone());
+                    },
+                },
+                (_, Some((key, value))) if key == "format" => {
+                    let format = as_lit_str(value)
+                        .ok_or_else(|| syn::Error::new_spanned(value, "Expected a string literal"))?;
+                    options.format = Some(format.clone());
+                },
+                (_, Some((key, Expr::Path(path)))) if key == "validate" => options.validate = Some(path.path.clone()),
+                _ => return Err(syn::Error::new_spanned(arg, "Unrecognized from_str option")),
+            }
+        }
+        Ok(options)
+    }
+}
+
+/// The `#[from_str("...")]` pattern a variant is parsed from, if it has one. The derived Display writes the same
+/// pattern back out, so the two round-trip.
+pub(crate) fn variant_pattern(variant: &Variant) -> syn::Result<Option<LitStr>> {
+    Ok(FromStrOptions::from_attribute(variant.get_attribute("from_str"))?.format)
+}
+
+/// One piece of a `#[from_str(format = "...")]` pattern: either literal text, or a field to parse.
+enum Segment {
+    Literal(String),
+    Field(Member),
+}
+
+/// Splits a pattern like `"{a}:{b}"` or `"range:{0}-{1}"` into its segments, checking that every field of the struct
+/// (or variant) appears exactly once, and that no two fields are adjacent (which would make the split ambiguous).
+fn parse_pattern(
+    pattern: &LitStr,
+    fields: &Fields,
+) -> syn::Result<Vec<Segment>> {
+    let error = |msg: String| syn::Error::new(pattern.span(), msg);
+    let mut segments: Vec<Segment> = Vec::new();
+    let mut literal = String::new();
+    let value = pattern.value();
+    let mut chars = value.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                literal.push('{');
+            },
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                literal.push('}');
+            },
+            '{' => {
+                let mut name = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => name.push(c),
+                        None => return Err(error("Unclosed `{` in pattern".to_string())),
+                    }
+                }
+                // `{r#type}` and `{type}` both refer to the `r#type` field.
+                let name = name.trim();
+                let name = name.strip_prefix("r#").unwrap_or(name);
+                let member = fields
+                    .members()
+                    .find(|member| match member {
+                        Member::Named(ident) => ident.unraw() == name,
+                        Member::Unnamed(index) => index.index.to_string() == name,
+                    })
+                    .ok_or_else(|| error(format!("No field `{}` found", name)))?;
+                match segments.last() {
+                    Some(Segment::Field(_)) if literal.is_empty() => {
+                        return Err(error(format!(
+                            "Field `{}` directly follows another field. Separate them with some literal text",
+                            name
+                        )))
+                    },
+                    _ => (),
+                }
+                if !literal.is_empty() {
+                    segments.push(Segment::Literal(std::mem::take(&mut literal)));
+                }
+                if segments.iter().any(|s| matches!(s, Segment::Field(m) if *m == member)) {
+                    return Err(error(format!("Field `{}` appears more than once", name)));
+                }
+                segments.push(Segment::Field(member));
+            },
+            '}' => return Err(error("Unmatched `}` in pattern".to_string())),
+            c => literal.push(c),
+        }
+    }
+    if !literal.is_empty() {
+        segments.push(Segment::Literal(literal));
+    }
+
+    for member in fields.members() {
+        if !segments.iter().any(|s| matches!(s, Segment::Field(m) if *m == member)) {
+            let name = match &member {
+                Member::Named(ident) => ident.unraw().to_string(),
+                Member::Unnamed(index) => index.index.to_string(),
+            };
+            return Err(error(format!("Field `{}` is missing from the pattern", name)));
+        }
+    }
+    Ok(segments)
+}
+
+/// Builds an expression that parses `s` into `constructor` (e.g. `Self` or `Self::Variant`) following `pattern`.
 ///
-/// Derefs to the field tagged with the `#[deref]` attribute  - if not found, it defaults to the first declared field in the struct.
-pub fn derive_trait(input: &DeriveInput) -> TokenStream {
+/// The expression evaluates to a `Result<Self, String>`, with the reason the input didn't match as the error.
+fn build_pattern_parser(
+    pattern: &LitStr,
+    fields: &Fields,
+    constructor: TokenStream,
+) -> syn::Result<TokenStream> {
+    let segments = parse_pattern(pattern, fields)?;
+    let binding = |member: &Member| match member {
+        Member::Named(ident) => format_ident!("__field_{}", ident),
+        Member::Unnamed(index) => format_ident!("__field_{}", index.index),
+    };
+    let field_type = |member: &Member| -> &Type {
+        let (_, field) = fields.members().zip(fields.iter()).find(|(m, _)| m == member).expect("Field not found");
+        &field.ty
+    };
+
+    let mut statements = Vec::new();
+    let mut segments_iter = segments.iter().peekable();
+    while let Some(segment) = segments_iter.next() {
+        match segment {
+            Segment::Literal(literal) => {
+                let expected = format!("expected \"{}\"", literal);
+                statements.push(quote!(
+                    let rest = rest.strip_prefix(#literal).ok_or_else(|| #expected.to_string())?;
+                ));
+            },
+            Segment::Field(member) => {
+                let binding = binding(member);
+                let ty = field_type(member);
+                let invalid = match member {
+                    Member::Named(ident) => format!("invalid value for `{}`: {{}}", ident.unraw()),
+                    Member::Unnamed(index) => format!("invalid value for field {}: {{}}", index.index),
+                };
+                let raw = match segments_iter.next() {
+                    Some(Segment::Literal(literal)) => {
+                        let expected = format!("expected \"{}\"", literal);
+                        quote!(
+                            let (raw, rest) = rest.split_once(#literal).ok_or_else(|| #expected.to_string())?;
+                        )
+                    },
+                    _ => quote!(let (raw, rest) = (rest, "");),
+                };
+                statements.push(quote!(
+                    #raw
+                    let #binding = raw.parse::<#ty>().map_err(|e| format!(#invalid, e))?;
+                ));
+            },
+        }
+    }
+
+    let bindings = fields.members().map(|member| binding(&member));
+    let construct = match fields {
+        Fields::Named(_) => {
+            let members = fields.members();
+            quote!(#constructor { #(#members: #bindings),* })
+        },
+        Fields::Unnamed(_) => quote!(#constructor(#(#bindings),*)),
+        Fields::Unit => quote!(#constructor),
+    };
+
+    Ok(quote!(
+        (|| -> ::std::result::Result<Self, ::std::string::String> {
+            let rest = s;
+            #(#statements)*
+            if !rest.is_empty() {
+                return Err(format!("unexpected trailing input \"{}\"", rest));
+            }
+            Ok(#construct)
+        })()
+    ))
+}
+
+fn build_struct_from_str(
+    input: &DeriveInput,
+    data: &DataStruct,
+) -> syn::Result<TokenStream> {
     let &DeriveInput {
         ident,
-        data,
+        vis,
         ..
     } = &input;
-    let Data::Enum(data) = data else {
-        panic!("this macro currently only supports no-field Enums")
+    let options = FromStrOptions::from_attribute(input.get_attribute("from_str"))?;
+    let error_ident = format_ident!("Parse{}Error", ident);
+    let error_doc = format!("The error returned when a string can't be parsed into a [`{}`].", ident);
+
+    if let Some(format) = &options.format {
+        let parser = build_pattern_parser(format, &data.fields, quote!(Self))?;
+        let err_msg = format!("\"{{}}\" is not a valid {}: {{}}", ident);
+        return Ok(quote!(
+            #[doc = #error_doc]
+            #[derive(Debug, Clone, PartialEq, Eq)]
+            #vis struct #error_ident {
+                /// The string that failed to parse.
+                pub input: String,
+                /// Why the string didn't match the expected format.
+                pub reason: String,
+            }
+
+            impl ::std::fmt::Display for #error_ident {
+                fn fmt(
+                    &self,
+                    f: &mut std::fmt::Formatter<'_>,
+                ) -> std::fmt::Result {
+                    write!(f, #err_msg, self.input, self.reason)
+                }
+            }
+
+            impl ::std::error::Error for #error_ident {}
+
+            impl ::std::str::FromStr for #ident {
+                type Err = #error_ident;
+                fn from_str(s: &str) -> Result<Self, Self::Err> {
+                    #parser.map_err(|reason| #error_ident {
+                        input: s.to_string(),
+                        reason,
+                    })
+                }
+            }
+        ));
+    }
+
+    let inner_type = match &data.fields {
+        Fields::Unnamed(fields) if fields.unnamed.len() == 1 => &fields.unnamed[0].ty,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                ident,
+                "FromStr can only be derived for newtypes (e.g. `struct Email(String)`) unless a pattern is provided with #[from_str(format = \"...\")]",
+            ))
+        },
+    };
+
+    let Some(validate) = &options.validate else {
+        return Ok(quote!(
+            impl ::std::str::FromStr for #ident {
+                type Err = <#inner_type as ::std::str::FromStr>::Err;
+                fn from_str(s: &str) -> Result<Self, Self::Err> {
+                    s.parse::<#inner_type>().map(Self)
+                }
+            }
+        ));
     };
 
-    let variants = data.variants.iter().map(|v| {
-        if v.fields.len() > 0 {
-            panic!("This macro currently only supports no-field Enums")
-        } else {
-            &v.ident
+    Ok(quote!(
+        #[doc = #error_doc]
+        #[derive(Debug)]
+        #vis enum #error_ident {
+            /// The string couldn't be parsed into the inner type.
+            Parse(<#inner_type as ::std::str::FromStr>::Err),
+            /// The parsed value was rejected by the validator, for the given reason.
+            Invalid(String),
         }
-    });
-    let variants_str = variants.clone().map(|v| v.to_string());
-    let err_msg = format!("{{}} is not a valid {}", &ident);
+
+        impl ::std::fmt::Display for #error_ident {
+            fn fmt(
+                &self,
+                f: &mut std::fmt::Formatter<'_>,
+            ) -> std::fmt::Result {
+                match self {
+                    Self::Parse(err) => write!(f, "{}", err),
+                    Self::Invalid(reason) => f.write_str(reason),
+                }
+            }
+        }
+
+        impl ::std::error::Error for #error_ident {}
+
+        impl ::std::str::FromStr for #ident {
+            type Err = #error_ident;
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                let inner = s.parse::<#inner_type>().map_err(#error_ident::Parse)?;
+                #validate(&inner).map_err(|reason| #error_ident::Invalid(reason.to_string()))?;
+                Ok(Self(inner))
+            }
+        }
+    ))
+}
+
+fn build_enum_from_str(
+    input: &DeriveInput,
+    data: &DataEnum,
+) -> syn::Result<TokenStream> {
+    let &DeriveInput {
+        ident,
+        vis,
+        ..
+    } = &input;
+    let enum_options = FromStrOptions::from_attribute(input.get_attribute("from_str"))?;
+    let normalize = |s: String| match enum_options.case_insensitive {
+        true => s.to_lowercase(),
+        false => s,
+    };
+
+    let mut seen: Vec<String> = Vec::new();
+    let mut valid_variants: Vec<String> = Vec::new();
+    let mut variant_arms = Vec::new();
+    let mut variant_parsers = Vec::new();
+    let mut missing_patterns = Vec::new();
+    for variant in &data.variants {
+        let variant_ident = &variant.ident;
+        let options = FromStrOptions::from_attribute(variant.get_attribute("from_str"))?;
+        if options.case_insensitive {
+            return Err(syn::Error::new_spanned(
+                variant,
+                "`case_insensitive` applies to the whole enum. Move it to the enum's #[from_str] attribute",
+            ));
+        }
+
+        if let Some(pattern) = &options.format {
+            variant_parsers.push(build_pattern_parser(pattern, &variant.fields, quote!(Self::#variant_ident))?);
+            valid_variants.push(pattern.value());
+            continue;
+        }
+        if !matches!(variant.fields, Fields::Unit) {
+            missing_patterns.push(format!("`{}`", variant_ident));
+            continue;
+        }
+
+        // The Display string comes first, so that every variant round-trips through `to_string()` / `parse()`.
+        let display_str = unit_variant_display_str(input, variant)?;
+        let mut accepted = vec![normalize(display_str.clone())];
+        valid_variants.push(display_str);
+        for alias in &options.aliases {
+            accepted.push(normalize(alias.value()));
+        }
+        for accepted_str in &accepted {
+            if seen.contains(accepted_str) {
+                return Err(syn::Error::new_spanned(
+                    variant,
+                    format!("\"{}\" is already accepted by another variant", accepted_str),
+                ));
+            }
+            seen.push(accepted_str.clone());
+        }
+
+        variant_arms.push(quote!(#(#accepted)|* => Ok(Self::#variant_ident)));
+    }
+    if !missing_patterns.is_empty() {
+        return Err(syn::Error::new_spanned(
+            ident,
+            format!(
+                "Variants with data need a pattern to be parsed from, e.g. #[from_str(\"range:{{0}}-{{1}}\")]. Missing a pattern: {}",
+                missing_patterns.join(", ")
+            ),
+        ));
+    }
+
+    let error_ident = format_ident!("Parse{}Error", ident);
+    let error_doc = format!("The error returned when a string can't be parsed into a [`{}`].", ident);
+    let err_msg = format!("\"{{}}\" is not a valid {}. Expected one of: {{}}", ident);
+    let match_input = match enum_options.case_insensitive {
+        true => quote!(s.to_lowercase().as_str()),
+        false => quote!(s),
+    };
 
     let tokens = quote!(
+        #[doc = #error_doc]
+        #[derive(Debug, Clone, PartialEq, Eq)]
+        #vis struct #error_ident {
+            /// The string that failed to parse.
+            pub input: String,
+            /// The strings accepted for each variant, as written by its `Display` (or the pattern it's parsed from).
+            pub valid_variants: &'static [&'static str],
+        }
+
+        impl ::std::fmt::Display for #error_ident {
+            fn fmt(
+                &self,
+                f: &mut std::fmt::Formatter<'_>,
+            ) -> std::fmt::Result {
+                write!(f, #err_msg, self.input, self.valid_variants.join(", "))
+            }
+        }
+
+        impl ::std::error::Error for #error_ident {}
+
         impl ::std::str::FromStr for #ident {
-            type Err = String;
+            type Err = #error_ident;
             fn from_str(s: &str) -> Result<Self, Self::Err> {
-                match s {
-                    #(#variants_str => Ok(Self::#variants),)*
-                    _ => Err(format!(#err_msg, s))
+                match #match_input {
+                    #(#variant_arms,)*
+                    _ => {
+                        #(
+                            if let Ok(item) = #variant_parsers {
+                                return Ok(item);
+                            }
+                        )*
+                        Err(#error_ident {
+                            input: s.to_string(),
+                            valid_variants: &[#(#valid_variants),*],
+                        })
+                    },
                 }
             }
         }
     );
-    tokens.into()
+    Ok(tokens)
+}
+
+fn build_from_str(input: &DeriveInput) -> syn::Result<TokenStream> {
+    match &input.data {
+        Data::Enum(data) => build_enum_from_str(input, data),
+        Data::Struct(data) => build_struct_from_str(input, data),
+        Data::Union(_) => Err(syn::Error::new_spanned(&input.ident, "FromStr can't be derived for unions")),
+    }
+}
+
+/// Logic for deriving FromStr.
+///
+/// For enums, each no-field variant is parsed from the same string the `Display` derive writes for it, so
+/// `#[display(rename = "...")]`, `#[display(rename_all = "...")]` and `#[display("...")]` are honored and the two
+/// derives always round-trip. Variants with data are parsed from a pattern, e.g. `#[from_str("range:{0}-{1}")]` on
+/// `Range(u32, u32)`, which are tried in declaration order (and case-sensitively) when no name matches. The derived
+/// Display writes the same pattern, and a `#[display("...")]` that disagrees with it is a compile error.
+///
+/// Alongside the `FromStr` impl, it generates a `Parse{Enum}Error` type (implementing `Display` and
+/// `std::error::Error`) that carries the rejected input and the list of valid variants.
+///
+/// Options:
+///  - `#[from_str(case_insensitive)]` on the enum matches the input regardless of case.
+///  - `#[from_str(alias = "...")]` (or `alias = ["...", "..."]`) on a variant accepts additional strings for it.
+///
+/// For newtypes (`struct Email(String)`), it delegates to the inner type's `FromStr` and wraps the result.
+/// `#[from_str(validate = path::to::fn)]` runs a `fn(&Inner) -> Result<(), impl Display>` check on the parsed
+/// value, in which case a `Parse{Struct}Error` enum is generated to hold either failure.
+///
+/// For other structs, `#[from_str(format = "{a}:{b}")]` parses each field (using its own `FromStr`) from the text
+/// between the literal parts of the pattern. Tuple fields are referenced by position, e.g. `"{0}-{1}"`.
+pub fn derive_trait(input: &DeriveInput) -> TokenStream {
+    build_from_str(input).unwrap_or_else(syn::Error::into_compile_error)
 }
@@ -1,3 +1,4 @@
+pub mod delegate;
 pub mod deref;
 pub mod display;
 pub mod from_string;
@@ -2,6 +2,7 @@ use proc_macro2::TokenStream;
 use quote::quote;
 use syn::{Data, DeriveInput, Field, Ident, Type};
 
+use super::build_routes::{find_id_field, is_generated_id, is_uuid};
 use crate::util::attribute_parsing::GetAttribute;
 
 pub fn derive_struct(input: &DeriveInput) -> TokenStream {
@@ -15,6 +16,13 @@ pub fn derive_struct(input: &DeriveInput) -> TokenStream {
         panic!("Only Structs are supported")
     };
 
+    let id_field = match find_id_field(input) {
+        Ok(id_field) => id_field,
+        Err(err) => return err.into_compile_error(),
+    };
+    let id_ident = &id_field.ident;
+    let is_generated_id = is_generated_id(id_field);
+
     match &data.fields {
         syn::Fields::Named(fields) => {
             let fields_filtered: Vec<&Field> = fields
@@ -23,8 +31,8 @@ pub fn derive_struct(input: &DeriveInput) -> TokenStream {
                 .filter(|field| {
                     // TODO: Abstract this for easier filtering
                     field.get_attribute("request_ignore").is_some() // TODO: `request(ignore)`
-                        || field.ident.as_ref().expect("Unexpected unnamed field found").to_string()
-                            != "id" // Always include "id"
+                        || !is_generated_id
+                        || field.ident != *id_ident
                 })
                 .collect();
             let field_names_filtered: Vec<&Ident> = fields_filtered
@@ -32,6 +40,12 @@ pub fn derive_struct(input: &DeriveInput) -> TokenStream {
                 .map(|f| f.ident.as_ref().expect("Found missing ident for field: {}"))
                 .collect();
             let field_types_filtered: Vec<&Type> = fields_filtered.iter().map(|f| &f.ty).collect();
+            // Other generated keys are a placeholder until the database assigns the real one on insert.
+            let generated_id = match (is_generated_id, is_uuid(&id_field.ty)) {
+                (true, true) => Some(quote!(#id_ident: uuid::Uuid::new_v4(),)),
+                (true, false) => Some(quote!(#id_ident: Default::default(),)),
+                (false, _) => None,
+            };
 
             let parse_args_impl_tokens = quote!(
                 impl<'a> tailwag::web::traits::rest_api::BuildCreateRoute<'a> for #ident
@@ -44,8 +58,7 @@ pub fn derive_struct(input: &DeriveInput) -> TokenStream {
                         impl Into<#ident> for Request {
                             fn into(self) -> #ident {
                                 #ident {
-                                    // TODO: Don't (necessarily) assume ID here
-                                    id: uuid::Uuid::new_v4(),
+                                    #generated_id
                                     #(#field_names_filtered: self.#field_names_filtered),*
                                     // ..Default::default() // Todo: Require default? Or how to handle non-magic fields?
                                 }
@@ -1,9 +1,26 @@
-use proc_macro2::TokenStream;
+use darling::{util::PathList, FromDeriveInput};
+use proc_macro2::{Span, TokenStream};
 use quote::quote;
-use syn::{punctuated::Punctuated, DeriveInput, Expr, ExprLit, ExprPath, Ident, Lit, Path, Token};
+use syn::{
+    ext::IdentExt, parse_quote, punctuated::Punctuated, Attribute, Data, DeriveInput, Expr, ExprLit, ExprPath, Field,
+    GenericArgument, Ident, Lit, Path, PathArguments, Token, Type,
+};
 use tailwag_utils::strings::ToSnakeCase;
 
-use crate::util::attribute_parsing::GetAttribute;
+use super::openapi::{build_openapi_paths, OpenApiChild, OpenApiRoutes};
+use crate::util::{
+    attribute_parsing::{as_flag, as_key_value, get_attribute_args, GetAttribute},
+    type_parsing::{get_json_schema_type, get_type_arguments, is_option},
+};
+
+/// The number of items returned by the list route when no `limit` is given.
+pub(crate) const DEFAULT_LIST_LIMIT: usize = 100;
+/// The most items the list route returns at once. A larger `limit` is clamped to this.
+pub(crate) const MAX_LIST_LIMIT: usize = 1000;
+/// The CRUD routes that can be turned off with `#[routes(only(...))]` / `#[routes(except(...))]`.
+const CRUD_ROUTES: [&str; 5] = ["list", "get", "post", "patch", "delete"];
+/// Query parameters used by the list route itself, which can't be shared with a `#[filterable]` / `#[sortable]` field.
+const RESERVED_LIST_PARAMS: [&str; 4] = ["limit", "offset", "after", "sort"];
 
 /// Helper function for extracting the route paths from an attribute.
 ///
@@ -59,11 +76,463 @@ fn get_route_paths(expr: Expr) -> (String, Ident, TokenStream) {
     }
 }
 
+/// Options parsed from the `#[routes(...)]` attribute on the struct.
+#[derive(Default, FromDeriveInput)]
+#[darling(default, attributes(routes))]
+struct RoutesOptions {
+    /// `#[routes(error_handler = path)]` - maps a `DataProvider` error to the `Response` returned by the default
+    /// handlers, in place of the generated `map_error`.
+    error_handler: Option<Path>,
+    /// `#[routes(provider = path::Type)]` - the `DataProvider` the default handlers use, in place of
+    /// `PostgresDataProvider`. Given without generics, it's used as `Type<Self>`.
+    provider: Option<Path>,
+    /// `#[routes(path = "/api/v2/brews")]` - the path the routes are mounted under, in place of the pluralized
+    /// snake_case name of the struct.
+    path: Option<String>,
+    /// `#[routes(get = handler)]` - replaces the default `GET /` list handler.
+    get: Option<Path>,
+    /// `#[routes(get_detail = handler)]` - replaces the default `GET /{id}` handler.
+    get_detail: Option<Path>,
+    /// `#[routes(post = handler)]` - replaces the default `POST /` handler.
+    post: Option<Path>,
+    /// `#[routes(patch = handler)]` - replaces the default `PATCH /{id}` handler.
+    patch: Option<Path>,
+    /// `#[routes(delete = handler)]` - replaces the default `DELETE /{id}` handler.
+    delete: Option<Path>,
+    /// `#[routes(only(list, get))]` - generates only the listed CRUD routes.
+    only: Option<PathList>,
+    /// `#[routes(except(delete))]` - generates every CRUD route except the listed ones.
+    except: Option<PathList>,
+    /// `#[routes(read_only)]` - shorthand for `only(list, get)`.
+    read_only: bool,
+}
+
+impl RoutesOptions {
+    /// Checks that `only` / `except` / `read_only` aren't combined, and that they only name known routes.
+    fn validate_routes(&self) -> syn::Result<()> {
+        let restrictions = [self.only.is_some(), self.except.is_some(), self.read_only];
+        if restrictions.iter().filter(|restricted| **restricted).count() > 1 {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "Only one of `only(...)`, `except(...)` and `read_only` can be used in #[routes(...)]",
+            ));
+        }
+        for route in self.only.iter().chain(self.except.iter()).flat_map(|routes| routes.iter()) {
+            if !CRUD_ROUTES.iter().any(|name| route.is_ident(name)) {
+                return Err(syn::Error::new_spanned(
+                    route,
+                    format!("Unknown route. Expected one of: {}", CRUD_ROUTES.join(", ")),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether the CRUD route (one of [CRUD_ROUTES]) should be generated.
+    fn is_enabled(
+        &self,
+        route: &str,
+    ) -> bool {
+        match (&self.only, &self.except, self.read_only) {
+            (Some(only), _, _) => only.iter().any(|path| path.is_ident(route)),
+            (_, Some(except), _) => !except.iter().any(|path| path.is_ident(route)),
+            (_, _, true) => matches!(route, "list" | "get"),
+            (None, None, false) => true,
+        }
+    }
+
+    /// The path the routes are mounted under, e.g. `/breweries` for `Brewery`, always with a leading `/` and no
+    /// trailing one.
+    fn route_path(
+        &self,
+        ident: &Ident,
+    ) -> String {
+        match &self.path {
+            Some(path) => format!("/{}", path.trim_matches('/')),
+            None => format!("/{}", pluralize(&ident.to_string().to_snake_case())),
+        }
+    }
+
+    /// The `DataProvider` type used by the default handlers to manage `item_type`.
+    fn provider_type(
+        &self,
+        item_type: &Type,
+    ) -> TokenStream {
+        match &self.provider {
+            // Already has its generics, e.g. `MyProvider::<Self, Config>`, so `Self` is swapped for the item type.
+            Some(path) if path.segments.last().is_some_and(|segment| !segment.arguments.is_empty()) => {
+                let mut path = path.clone();
+                if let Some(PathArguments::AngleBracketed(args)) = path.segments.last_mut().map(|s| &mut s.arguments) {
+                    for arg in args.args.iter_mut() {
+                        if matches!(arg, GenericArgument::Type(Type::Path(ty)) if ty.path.is_ident("Self")) {
+                            *arg = GenericArgument::Type(item_type.clone());
+                        }
+                    }
+                }
+                quote!(#path)
+            },
+            Some(path) => quote!(#path<#item_type>),
+            None => quote!(tailwag::orm::data_manager::PostgresDataProvider<#item_type>),
+        }
+    }
+}
+
+/// A child resource of the struct, from `#[children(Beer, via = brewery_id)]`.
+struct ChildRoutes {
+    /// The child's type, e.g. `Beer`.
+    child: Path,
+    /// The field on the child that holds its parent's id, e.g. `brewery_id`.
+    via: Ident,
+}
+
+impl ChildRoutes {
+    fn from_attribute(attr: &Attribute) -> syn::Result<Self> {
+        let mut child = None;
+        let mut via = None;
+        for arg in get_attribute_args(attr)? {
+            match (&arg, as_key_value(&arg)) {
+                (Expr::Path(path), _) if child.is_none() => child = Some(path.path.clone()),
+                (_, Some((key, Expr::Path(value)))) if key == "via" && value.path.get_ident().is_some() => {
+                    via = value.path.get_ident().cloned()
+                },
+                _ => return Err(syn::Error::new_spanned(arg, "Expected `#[children(ChildType, via = parent_id_field)]`")),
+            }
+        }
+        match (child, via) {
+            (Some(child), Some(via)) => Ok(Self {
+                child,
+                via,
+            }),
+            _ => Err(syn::Error::new_spanned(attr, "Expected `#[children(ChildType, via = parent_id_field)]`")),
+        }
+    }
+
+    /// The path the children are nested under, e.g. `/{id}/beers`.
+    fn route_path(&self) -> String {
+        let child_name = self.child.segments.last().map(|segment| segment.ident.to_string()).unwrap_or_default();
+        format!("/{{id}}/{}", pluralize(&child_name.to_snake_case()))
+    }
+}
+
+/// Naively pluralizes a snake_case name for a route path, e.g. `beer` -> `beers` or `brewery` -> `breweries`.
+fn pluralize(name: &str) -> String {
+    let ends_with_consonant_y = name.ends_with('y')
+        && !name.ends_with("ay")
+        && !name.ends_with("ey")
+        && !name.ends_with("oy")
+        && !name.ends_with("uy");
+    match name {
+        _ if ends_with_consonant_y => format!("{}ies", &name[..name.len() - 1]),
+        _ if ["s", "x", "z", "ch", "sh"].iter().any(|suffix| name.ends_with(suffix)) => format!("{}es", name),
+        _ => format!("{}s", name),
+    }
+}
+
+/// Finds the primary key of the struct: the field tagged `#[id]`, or the field named `id` if none are tagged.
+///
+/// The key can be any type that implements `FromStr` and `Display`, since it's parsed from the `/{id}` path and from
+/// `after` cursors. Only single-field keys are supported.
+pub(crate) fn find_id_field(input: &DeriveInput) -> syn::Result<&Field> {
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new_spanned(&input.ident, "BuildRoutes can only be derived for structs"));
+    };
+    let mut tagged = data.fields.iter().filter(|f| f.get_attribute("id").is_some());
+    match tagged.next() {
+        Some(id_field) => match tagged.next() {
+            Some(duplicate) => Err(syn::Error::new_spanned(
+                duplicate,
+                "Only one field can be tagged with #[id], since composite keys aren't supported",
+            )),
+            None => {
+                id_source_override(id_field)?;
+                Ok(id_field)
+            },
+        },
+        None => data.fields.iter().find(|f| f.ident.as_ref().is_some_and(|ident| ident == "id")).ok_or_else(|| {
+            syn::Error::new_spanned(
+                &input.ident,
+                "No id field found. Add an `id` field, or tag the primary key with #[id].",
+            )
+        }),
+    }
+}
+
+/// Parses `#[id(generated)]` / `#[id(provided)]` into whether the id is generated, or `None` for a bare `#[id]`.
+fn id_source_override(id_field: &Field) -> syn::Result<Option<bool>> {
+    let Some(attr) = id_field.get_attribute("id") else {
+        return Ok(None);
+    };
+    let args = get_attribute_args(attr)?;
+    match args.as_slice() {
+        [] => Ok(None),
+        [arg] if as_flag(arg).is_some_and(|flag| flag == "generated") => Ok(Some(true)),
+        [arg] if as_flag(arg).is_some_and(|flag| flag == "provided") => Ok(Some(false)),
+        _ => Err(syn::Error::new_spanned(attr, "Expected `#[id]`, `#[id(generated)]` or `#[id(provided)]`")),
+    }
+}
+
+/// Whether new items get a generated id, which is left out of the create request.
+///
+/// By default, UUID keys are generated, and so are integer keys, which are assigned by the database (e.g. a
+/// `BIGSERIAL` column). Any other key (slugs, etc.) is provided in the create request. Override the default with
+/// `#[id(generated)]` or `#[id(provided)]`. A generated key other than a `Uuid` needs to implement `Default`, which
+/// is the placeholder sent to the provider until the database assigns the real one.
+pub(crate) fn is_generated_id(id_field: &Field) -> bool {
+    match id_source_override(id_field) {
+        Ok(Some(is_generated)) => is_generated,
+        _ => is_uuid(&id_field.ty) || matches!(get_json_schema_type(&id_field.ty), Some(("integer", _))),
+    }
+}
+
+/// Whether the type is a `Uuid`, which the create route generates itself instead of leaving it to the database.
+pub(crate) fn is_uuid(ty: &Type) -> bool {
+    matches!(ty, Type::Path(path) if path.path.segments.last().is_some_and(|segment| segment.ident == "Uuid"))
+}
+
+/// Builds the `ListQuery` struct parsed from the list route's query string, along with the default list handler.
+///
+/// Everything is pushed down into the provider's query, instead of loading every item:
+///  - Filters are generated for every field tagged `#[filterable]`, matching items whose field equals the given
+///    value. Optional fields are matched by their inner value, so `?field=x` matches `Some(x)`.
+///  - Fields tagged `#[sortable]` can be sorted on, in order, with `sort=field,-other` (`-` for descending). These
+///    need to be orderable by the provider, which is why sorting is opted into separately from filtering.
+///  - Items are always ordered by id last, so pages are stable. Results are paged with `limit` (clamped to
+///    [MAX_LIST_LIMIT], and [DEFAULT_LIST_LIMIT] when not given) / `offset`, or with `after=<id>` to continue after
+///    the last item of the previous page. `after` is a keyset cursor (`id > after`), so it keeps working when that
+///    item is deleted, but it can't be combined with `sort`.
+fn build_list_handler(
+    input: &DeriveInput,
+    id_ident: &Ident,
+    id_type: &Type,
+    provider_type: &TokenStream,
+    error_handler: &TokenStream,
+) -> syn::Result<(TokenStream, TokenStream)> {
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new_spanned(&input.ident, "BuildRoutes can only be derived for structs"));
+    };
+    let mut filter_idents = Vec::new();
+    let mut filter_types = Vec::new();
+    let mut filter_values = Vec::new();
+    let mut sort_idents = Vec::new();
+    for field in &data.fields {
+        for attribute in ["filterable", "sortable"] {
+            if field.get_attribute(attribute).is_none() {
+                continue;
+            }
+            let Some(field_ident) = &field.ident else {
+                return Err(syn::Error::new_spanned(
+                    field,
+                    format!("#[{}] is only supported on named fields", attribute),
+                ));
+            };
+            if RESERVED_LIST_PARAMS.contains(&field_ident.to_string().as_str()) {
+                return Err(syn::Error::new_spanned(
+                    field_ident,
+                    format!("`{}` is reserved for the list route, and can't be #[{}]", field_ident, attribute),
+                ));
+            }
+            if attribute == "sortable" {
+                sort_idents.push(field_ident);
+                continue;
+            }
+            let (filter_type, filter_value) = match is_option(field) {
+                true => (
+                    get_type_arguments(&field.ty).first().copied().unwrap_or(&field.ty),
+                    quote!(Some(value.clone())),
+                ),
+                false => (&field.ty, quote!(value.clone())),
+            };
+            filter_idents.push(field_ident);
+            filter_types.push(filter_type);
+            filter_values.push(filter_value);
+        }
+    }
+    let filter_import =
+        (!filter_idents.is_empty()).then(|| quote!(use tailwag::orm::queries::filterable_types::FilterEq;));
+    let sort_strs: Vec<String> = sort_idents.iter().map(|ident| ident.to_string()).collect();
+    // Without any #[sortable] fields, every `sort` is rejected.
+    let sort = match sort_idents.is_empty() {
+        true => quote!(
+            if query.sort.as_deref().is_some_and(|sort| !sort.trim().is_empty()) {
+                return Err(error_response(HttpStatus::BadRequest, "This list can't be sorted"));
+            }
+        ),
+        false => quote!(
+            if let Some(sort) = &query.sort {
+                for key in sort.split(',').map(str::trim).filter(|key| !key.is_empty()) {
+                    let (field, descending) = match key.strip_prefix('-') {
+                        Some(field) => (field, true),
+                        None => (key, false),
+                    };
+                    items = match (field, descending) {
+                        #((#sort_strs, false) => items.order_by(|item| item.#sort_idents.asc()),)*
+                        #((#sort_strs, true) => items.order_by(|item| item.#sort_idents.desc()),)*
+                        _ => {
+                            return Err(error_response(HttpStatus::BadRequest, format!("Can't sort by `{}`", field)))
+                        },
+                    };
+                }
+            }
+        ),
+    };
+
+    let list_query = quote!(
+        #[derive(serde::Deserialize)]
+        struct ListQuery {
+            limit: Option<usize>,
+            offset: Option<usize>,
+            after: Option<String>,
+            sort: Option<String>,
+            #(#filter_idents: Option<#filter_types>,)*
+        }
+    );
+    let handler = quote!(
+        |tailwag::web::application::http::route::QueryParams(query): tailwag::web::application::http::route::QueryParams<ListQuery>, provider: #provider_type| async move {
+            use tailwag::orm::queries::filterable_types::{FilterCompare, Sortable};
+            #filter_import
+            let mut items = provider.query();
+            #(
+                if let Some(value) = &query.#filter_idents {
+                    items = items.filter(|item| item.#filter_idents.eq(#filter_values));
+                }
+            )*
+            #sort
+            items = items.order_by(|item| item.#id_ident.asc());
+
+            if let Some(after) = &query.after {
+                if query.sort.is_some() {
+                    return Err(error_response(HttpStatus::BadRequest, "`after` can't be combined with `sort`"));
+                }
+                let after = after
+                    .parse::<#id_type>()
+                    .map_err(|_| error_response(HttpStatus::BadRequest, format!("Invalid cursor `{}`", after)))?;
+                items = items.filter(|item| item.#id_ident.gt(after.clone()));
+            }
+            items
+                .offset(query.offset.unwrap_or(0))
+                .limit(query.limit.unwrap_or(#DEFAULT_LIST_LIMIT).min(#MAX_LIST_LIMIT))
+                .execute()
+                .await
+                .map_err(#error_handler)
+        }
+    );
+    Ok((list_query, handler))
+}
+
+/// Logic for deriving BuildRoutes, which builds the default CRUD routes for a type along with any `#[actions]` /
+/// `#[views]`.
+///
+/// Any of the default handlers can be replaced with your own, e.g.
+/// `#[routes(get = list_fn, get_detail = get_fn, post = create_fn, patch = update_fn, delete = delete_fn)]`.
+/// Unknown keys in `#[routes(...)]` are a compile error.
+///
+/// Also generates `fn openapi_paths() -> serde_json::Value`, the OpenAPI 3.1 `paths` object describing every mounted
+/// route. The request and response schemas are derived from the struct's fields the same way as `JsonSchema`, so
+/// nested structs and enums that derive `JsonSchema` are described too, while any other type accepts anything. Child
+/// routes are described by the child's `JsonSchema` the same way. The `#[actions]` / `#[views]` and any overridden
+/// handlers are only listed with a summary, since their requests and responses are up to the handler.
+///
+/// CRUD routes can be left out with `#[routes(only(list, get))]` or `#[routes(except(delete))]`, using the names
+/// `list`, `get`, `post`, `patch` and `delete`. `#[routes(read_only)]` is shorthand for `only(list, get)`.
+///
+/// The default handlers never panic on a failed `DataProvider` call. Errors are mapped to a `Response` with a JSON
+/// body of `{ "error": "..." }`: 404 when the item isn't found, 409 for constraint violations, and 500 for anything
+/// else. The `sqlx::Error` is found anywhere in the error's `source()` chain, so providers that wrap it in their own
+/// error type are mapped the same way. The error itself is only logged, since its message can name tables and
+/// constraints, and the client gets a fixed message for the status. Provide your own mapping with
+/// `#[routes(error_handler = path)]`, where `path` is a `fn(Error) -> Response` for the provider's error type.
+///
+/// The list route supports paging (`limit` / `offset`, or `after=<id>`), filtering on any fields tagged
+/// `#[filterable]`, and sorting on any fields tagged `#[sortable]`. See [build_list_handler] for the details.
+///
+/// The handlers use `PostgresDataProvider<Self>` by default. Swap in any other `DataProvider<Self>` with
+/// `#[routes(provider = path::Type)]`, e.g. `#[cfg_attr(test, routes(provider = InMemoryProvider))]` to run the same
+/// routes against an in-memory provider in unit tests. Its error type needs to implement `std::error::Error`, unless
+/// you also provide an `error_handler`.
+///
+/// The routes are mounted under `route_path()`, which defaults to the pluralized snake_case name of the struct
+/// (`/breweries` for `Brewery`). Override it with `#[routes(path = "/api/v2/brews")]`.
+///
+/// Items are looked up by the field tagged `#[id]` (or named `id`), which can be any `FromStr` + `Display` type.
+/// UUID and integer keys are generated for new items, so `POST /` rejects a body that sets them, while any other key
+/// is sent in the create request. Override this with `#[id(generated)]` or `#[id(provided)]`. Composite keys aren't
+/// supported.
+///
+/// Child resources get nested list / create routes with `#[children(Beer, via = brewery_id)]`, where `brewery_id` is
+/// the field on `Beer` holding its parent's id: `GET /{id}/beers` lists the parent's beers, and `POST /{id}/beers`
+/// creates one with `brewery_id` set from the path. Both return a 404 if the parent doesn't exist, and the list is
+/// paged with `limit` / `offset` like the top-level list. The beers are queried with `brewery_id = id`, so
+/// `brewery_id` needs to be one of `Beer`'s filters.
 pub fn derive_struct(input: &DeriveInput) -> TokenStream {
     let &DeriveInput {
         ident,
         ..
     } = &input;
+    let Data::Struct(data) = &input.data else {
+        return syn::Error::new_spanned(ident, "BuildRoutes can only be derived for structs").into_compile_error();
+    };
+    let id_field = match find_id_field(input) {
+        Ok(id_field) => id_field,
+        Err(err) => return err.into_compile_error(),
+    };
+    let id_type = &id_field.ty;
+    let Some(id_ident) = &id_field.ident else {
+        return syn::Error::new_spanned(id_field, "#[id] is only supported on named fields").into_compile_error();
+    };
+    let id_str = id_ident.to_string();
+    let options = match RoutesOptions::from_derive_input(input) {
+        Ok(options) => options,
+        Err(err) => return err.write_errors(),
+    };
+    if let Err(err) = options.validate_routes() {
+        return err.into_compile_error();
+    }
+    let provider_type = options.provider_type(&parse_quote!(Self));
+    // Helper fns nested in `build_routes()` can't refer to `Self`, so they name the struct instead.
+    let item_provider_type = options.provider_type(&parse_quote!(#ident));
+    let error_handler = options.error_handler.as_ref().map_or(quote!(map_error), |path| quote!(#path));
+    // Only generated when it's used, since a custom error handler replaces it entirely.
+    let default_error_handler = options.error_handler.is_none().then(|| {
+        quote!(
+            #[allow(dead_code)]
+            fn map_error<E: ::std::error::Error + 'static>(error: E) -> Response {
+                use tailwag::orm::sqlx;
+                // Providers may wrap the `sqlx::Error` in their own error type, so look through the whole chain.
+                let sqlx_error = ::std::iter::successors(
+                    Some(&error as &(dyn ::std::error::Error + 'static)),
+                    |error| error.source(),
+                )
+                .find_map(|error| error.downcast_ref::<sqlx::Error>());
+                let status = match sqlx_error {
+                    Some(sqlx::Error::RowNotFound) => HttpStatus::NotFound,
+                    Some(sqlx::Error::Database(db_error)) if matches!(
+                        db_error.kind(),
+                        sqlx::error::ErrorKind::UniqueViolation
+                            | sqlx::error::ErrorKind::ForeignKeyViolation
+                            | sqlx::error::ErrorKind::NotNullViolation
+                            | sqlx::error::ErrorKind::CheckViolation
+                    ) => HttpStatus::Conflict,
+                    _ => HttpStatus::InternalServerError,
+                };
+                // The provider's message can name tables and constraints, so it's only logged, never sent to the
+                // client.
+                match status {
+                    HttpStatus::NotFound => {
+                        tailwag::log::debug!("Item not found handling request: {}", error);
+                        not_found()
+                    },
+                    HttpStatus::Conflict => {
+                        tailwag::log::debug!("Conflict handling request: {}", error);
+                        error_response(status, "Conflicts with an existing item")
+                    },
+                    _ => {
+                        tailwag::log::error!("Unexpected error handling request: {}", error);
+                        error_response(status, "Internal server error")
+                    },
+                }
+            }
+        )
+    });
 
     fn extract_routes_from_attribute(
         input: &DeriveInput,
@@ -104,35 +573,39 @@ pub fn derive_struct(input: &DeriveInput) -> TokenStream {
     }
     let list_policy = extract_policy!("list_policy");
 
-    // Default CRUD routes. Can be overridden with #[get(func_name)]
-    let get_list_route_tokens = input
-        .get_attribute("get")
-        .map(|attr| attr.parse_args::<Ident>().expect("Unable to pare get attribute"))
+    // Default CRUD routes. Can be overridden with #[routes(get = func_name)]
+    let list_handler = build_list_handler(input, id_ident, id_type, &provider_type, &error_handler);
+    let (list_query, list_handler) = match list_handler {
+        Ok(tokens) => tokens,
+        Err(err) => return err.into_compile_error(),
+    };
+    // The query struct is only needed by the default handler.
+    let list_query = (options.get.is_none() && options.is_enabled("list")).then_some(list_query);
+    let get_list_route_tokens = options
+        .get
+        .as_ref()
         .map(|func_name| quote!(.get_with_policy("/", #func_name, #list_policy)))
         .unwrap_or(quote!(
             .get_with_policy(
                 "/",
-                |provider: tailwag::orm::data_manager::PostgresDataProvider<Self>| async move {
-                    provider.all().await.unwrap().collect::<Vec<_>>()
-                },
+                #list_handler,
                 #list_policy
             )
         ));
 
     let get_policy = extract_policy!("get_policy");
-    let get_detail_route_tokens = input
-        .get_attribute("get_id")
-        .map(|attr| attr.parse_args::<Ident>().unwrap())
+    let get_detail_route_tokens = options
+        .get_detail
+        .as_ref()
         .map(|func_name| quote!(.get_with_policy("/{id}", #func_name, #get_policy)))
         .unwrap_or(quote!(
             .with_handler(
                 tailwag::web::application::http::route::HttpMethod::Get,
                 "/{id}",
-                |tailwag::web::application::http::route::PathVariable(id): tailwag::web::application::http::route::PathString, provider: tailwag::orm::data_manager::PostgresDataProvider<Self>|{
-                    use tailwag::orm::queries::filterable_types::FilterEq;
+                |tailwag::web::application::http::route::PathVariable(id): tailwag::web::application::http::route::PathString, provider: #provider_type|{
                     async move {
-                        let id = uuid::Uuid::parse_str(&id).ok()?;
-                        provider.get(|item|item.id.eq(id)).await.ok()
+                        let id = parse_id(&id)?;
+                        find_by_id(&provider, &id).await
                     }
                 },
                 #get_policy
@@ -141,32 +614,77 @@ pub fn derive_struct(input: &DeriveInput) -> TokenStream {
     );
 
     let post_policy = extract_policy!("post_policy");
-    let post_create_route_tokens = input
-        .get_attribute("post")
-        .map(|attr| attr.parse_args::<Path>().unwrap())
+    let id_key = id_ident.unraw().to_string();
+    let generated_id = match (is_generated_id(id_field), is_uuid(id_type)) {
+        (true, true) => Some(quote!(<#id_type>::new_v4())),
+        (true, false) => Some(quote!(<#id_type as ::std::default::Default>::default())),
+        (false, _) => None,
+    };
+    let post_create_route_tokens = options
+        .post
+        .as_ref()
         .map(|func_name| quote!(.post_with_policy("/", #func_name, #post_policy)))
-        .unwrap_or(quote!(
-            .post_with_policy(
-                "/",
-                |item: <Self as tailwag::orm::queries::Insertable>::CreateRequest, provider: tailwag::orm::data_manager::PostgresDataProvider<Self>| async move {
-                    provider.create(item.into()).await.unwrap()
-                },
-                #post_policy
-            )
-        )
-    );
+        .unwrap_or(match generated_id {
+            // The generated id is added to the body before it's parsed, so it's ignored by a `CreateRequest` that
+            // leaves the id out.
+            Some(generated_id) => quote!(
+                .post_with_policy(
+                    "/",
+                    |mut body: serde_json::Value, provider: #provider_type| async move {
+                        let fields = body
+                            .as_object_mut()
+                            .ok_or_else(|| error_response(HttpStatus::BadRequest, "Expected a JSON object"))?;
+                        if fields.contains_key(#id_key) {
+                            return Err(error_response(
+                                HttpStatus::BadRequest,
+                                format!("`{}` is generated, and can't be set", #id_key),
+                            ));
+                        }
+                        let id = serde_json::to_value(#generated_id)
+                            .map_err(|err| error_response(HttpStatus::InternalServerError, err))?;
+                        fields.insert(#id_key.to_string(), id);
+                        let item: <Self as tailwag::orm::queries::Insertable>::CreateRequest =
+                            serde_json::from_value(body).map_err(|err| error_response(HttpStatus::BadRequest, err))?;
+                        provider.create(item.into()).await.map_err(#error_handler)
+                    },
+                    #post_policy
+                )
+            ),
+            None => quote!(
+                .post_with_policy(
+                    "/",
+                    |item: <Self as tailwag::orm::queries::Insertable>::CreateRequest, provider: #provider_type| async move {
+                        provider.create(item.into()).await.map_err(#error_handler)
+                    },
+                    #post_policy
+                )
+            ),
+        });
 
     let patch_policy = extract_policy!("patch_policy");
-    let patch_edit_route_tokens = input
-        .get_attribute("patch")
-        .map(|attr| attr.parse_args::<Ident>().unwrap())
-            // TODO: Fix this to take /{id} instead of the whole item
-        .map(|func_name| quote!(.patch_with_policy("/", #func_name, #patch_policy)))
+    let patch_edit_route_tokens = options
+        .patch
+        .as_ref()
+        .map(|func_name| quote!(.patch_with_policy("/{id}", #func_name, #patch_policy)))
         .unwrap_or(quote!(
-            .patch_with_policy(
-                "/",
-                |item: Self, provider: tailwag::orm::data_manager::PostgresDataProvider<Self>| async move {
-                    provider.update(&item).await.unwrap()
+            // Applies a partial update: only the fields present in the body are changed, and the id is never overwritten.
+            .with_handler(
+                tailwag::web::application::http::route::HttpMethod::Patch,
+                "/{id}",
+                |tailwag::web::application::http::route::PathVariable(id): tailwag::web::application::http::route::PathString, changes: serde_json::Value, provider: #provider_type|{
+                    async move {
+                        let id = parse_id(&id)?;
+                        let item = find_by_id(&provider, &id).await?;
+                        let mut merged = serde_json::to_value(&item)
+                            .map_err(|err| error_response(HttpStatus::InternalServerError, err))?;
+                        if let (Some(fields), serde_json::Value::Object(changes)) = (merged.as_object_mut(), changes) {
+                            fields.extend(changes.into_iter().filter(|(field, _)| field != #id_str));
+                        }
+                        let item: Self = serde_json::from_value(merged)
+                            .map_err(|err| error_response(HttpStatus::BadRequest, err))?;
+                        provider.update(&item).await.map_err(#error_handler)?;
+                        Ok::<_, Response>(item)
+                    }
                 },
                 #patch_policy
             )
@@ -174,34 +692,210 @@ pub fn derive_struct(input: &DeriveInput) -> TokenStream {
     );
 
     let delete_policy = extract_policy!("delete_policy");
-    let delete_route_tokens  = input
-        .get_attribute("delete")
-        .map(|attr| attr.parse_args::<Ident>().unwrap())
-        .map(|func_name| quote!(.delete_with_policy("/", #func_name, #delete_policy)))
+    let delete_route_tokens  = options
+        .delete
+        .as_ref()
+        .map(|func_name| quote!(.delete_with_policy("/{id}", #func_name, #delete_policy)))
         .unwrap_or(quote!(
-            // TODO: Fix this to take /{id} instead of the whole item
-            .delete_with_policy(
-                "/",
-                |item: Self, provider: tailwag::orm::data_manager::PostgresDataProvider<Self>| async move {
-                    provider.delete(item).await.unwrap()
+            .with_handler(
+                tailwag::web::application::http::route::HttpMethod::Delete,
+                "/{id}",
+                |tailwag::web::application::http::route::PathVariable(id): tailwag::web::application::http::route::PathString, provider: #provider_type|{
+                    async move {
+                        let id = parse_id(&id)?;
+                        let item = find_by_id(&provider, &id).await?;
+                        provider.delete(item).await.map_err(#error_handler)?;
+                        Ok::<_, Response>(Response::no_content())
+                    }
                 },
                 #delete_policy
             )
         )
     );
 
-    let _route_path = ident.to_string().to_snake_case().to_string();
+    // Nested routes for child resources, scoped by the parent's id: `GET /{id}/beers` and `POST /{id}/beers`.
+    let children = match input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("children"))
+        .map(ChildRoutes::from_attribute)
+        .collect::<syn::Result<Vec<_>>>()
+    {
+        Ok(children) => children,
+        Err(err) => return err.into_compile_error(),
+    };
+    let child_route_tokens = children.iter().map(|child_routes| {
+        let ChildRoutes {
+            child,
+            via,
+        } = child_routes;
+        let child_path = child_routes.route_path();
+        let child_provider_type = options.provider_type(&parse_quote!(#child));
+        let via_str = via.to_string();
+        let list_tokens = options.is_enabled("list").then(|| {
+            quote!(
+                .with_handler(
+                    tailwag::web::application::http::route::HttpMethod::Get,
+                    #child_path,
+                    |tailwag::web::application::http::route::PathVariable(id): tailwag::web::application::http::route::PathString, tailwag::web::application::http::route::QueryParams(page): tailwag::web::application::http::route::QueryParams<PageQuery>, parent_provider: #provider_type, provider: #child_provider_type|{
+                        async move {
+                            let id = parse_id(&id)?;
+                            find_by_id(&parent_provider, &id).await?;
+                            use tailwag::orm::queries::filterable_types::FilterEq;
+                            provider
+                                .query()
+                                .filter(|child| child.#via.eq(id.clone()))
+                                .offset(page.offset.unwrap_or(0))
+                                .limit(page.limit.unwrap_or(#DEFAULT_LIST_LIMIT).min(#MAX_LIST_LIMIT))
+                                .execute()
+                                .await
+                                .map_err(#error_handler)
+                        }
+                    },
+                    #list_policy
+                )
+            )
+        });
+        let create_tokens = options.is_enabled("post").then(|| {
+            quote!(
+                .with_handler(
+                    tailwag::web::application::http::route::HttpMethod::Post,
+                    #child_path,
+                    |tailwag::web::application::http::route::PathVariable(id): tailwag::web::application::http::route::PathString, mut body: serde_json::Value, parent_provider: #provider_type, provider: #child_provider_type|{
+                        async move {
+                            let id = parse_id(&id)?;
+                            find_by_id(&parent_provider, &id).await?;
+                            // The parent's id always comes from the path, never the body.
+                            let parent_id = serde_json::to_value(&id)
+                                .map_err(|err| error_response(HttpStatus::InternalServerError, err))?;
+                            body.as_object_mut()
+                                .ok_or_else(|| error_response(HttpStatus::BadRequest, "Expected a JSON object"))?
+                                .insert(#via_str.to_string(), parent_id);
+                            let item: <#child as tailwag::orm::queries::Insertable>::CreateRequest = serde_json::from_value(body)
+                                .map_err(|err| error_response(HttpStatus::BadRequest, err))?;
+                            provider.create(item.into()).await.map_err(#error_handler)
+                        }
+                    },
+                    #post_policy
+                )
+            )
+        });
+        quote!(#list_tokens #create_tokens)
+    });
+
+    // Routes turned off with `only(...)` / `except(...)` / `read_only` are left out entirely.
+    let get_list_route_tokens = options.is_enabled("list").then_some(get_list_route_tokens);
+    let get_detail_route_tokens = options.is_enabled("get").then_some(get_detail_route_tokens);
+    let post_create_route_tokens = options.is_enabled("post").then_some(post_create_route_tokens);
+    let patch_edit_route_tokens = options.is_enabled("patch").then_some(patch_edit_route_tokens);
+    let delete_route_tokens = options.is_enabled("delete").then_some(delete_route_tokens);
+
+    let route_path = options.route_path(ident);
+    let crud_routes = [
+        ("list", options.get.as_ref()),
+        ("get", options.get_detail.as_ref()),
+        ("post", options.post.as_ref()),
+        ("patch", options.patch.as_ref()),
+        ("delete", options.delete.as_ref()),
+    ];
+    let openapi_paths_tokens = match build_openapi_paths(&OpenApiRoutes {
+        ident,
+        route_path: route_path.clone(),
+        fields: data.fields.iter().collect(),
+        id_field,
+        crud_routes: crud_routes.into_iter().filter(|(route, _)| options.is_enabled(route)).collect(),
+        children: children
+            .iter()
+            .map(|child_routes| OpenApiChild {
+                path: child_routes.route_path(),
+                ty: {
+                    let child = &child_routes.child;
+                    parse_quote!(#child)
+                },
+                via: child_routes.via.unraw().to_string(),
+            })
+            .collect(),
+        actions: &action_paths,
+        views: &views_paths,
+    }) {
+        Ok(tokens) => tokens,
+        Err(err) => return err.into_compile_error(),
+    };
+    // Only generated when a default handler looks items up by id, since overridden handlers might use a type that
+    // the provider can't query.
+    let needs_lookup = [("get", &options.get_detail), ("patch", &options.patch), ("delete", &options.delete)]
+        .iter()
+        .any(|(route, handler)| handler.is_none() && options.is_enabled(route))
+        || (!children.is_empty() && (options.is_enabled("list") || options.is_enabled("post")));
+    // The paging of the nested child lists, which matches the top-level list's `limit` / `offset`.
+    let page_query = (!children.is_empty() && options.is_enabled("list")).then(|| {
+        quote!(
+            #[derive(serde::Deserialize)]
+            struct PageQuery {
+                limit: Option<usize>,
+                offset: Option<usize>,
+            }
+        )
+    });
+    let lookup_helpers = needs_lookup.then(|| {
+        quote!(
+            fn parse_id(id: &str) -> Result<#id_type, Response> {
+                id.parse::<#id_type>().map_err(|_| not_found())
+            }
+            /// Loads an item by its id. `DataProvider::get` gives `Ok(None)` for a missing item, which is a 404.
+            #[allow(clippy::ptr_arg)]
+            async fn find_by_id(
+                provider: &#item_provider_type,
+                id: &#id_type,
+            ) -> Result<#ident, Response> {
+                use tailwag::orm::queries::filterable_types::FilterEq;
+                provider
+                    .get(|item| item.#id_ident.eq(id.clone()))
+                    .await
+                    .map_err(#error_handler)?
+                    .ok_or_else(not_found)
+            }
+        )
+    });
     let parse_args_impl_tokens = quote!(
+        impl #ident {
+            #openapi_paths_tokens
+        }
+
         impl tailwag::web::traits::rest_api::BuildRoutes<#ident> for #ident
         {
+            fn route_path() -> &'static str {
+                #route_path
+            }
+
             fn build_routes()  -> tailwag::web::application::http::route::Route {
                 use tailwag::orm::data_manager::traits::DataProvider;
+                use tailwag::web::application::http::route::{HttpStatus, Response};
+
+                // Not every helper is used when routes are overridden or left out.
+                #[allow(dead_code)]
+                fn error_response(
+                    status: HttpStatus,
+                    message: impl ::std::fmt::Display,
+                ) -> Response {
+                    Response::json(status, serde_json::json!({ "error": message.to_string() }))
+                }
+                #[allow(dead_code)]
+                fn not_found() -> Response {
+                    error_response(HttpStatus::NotFound, "Not found")
+                }
+                #default_error_handler
+                #lookup_helpers
+                #list_query
+                #page_query
+
                 tailwag::web::application::http::route::Route::new()
                     #get_list_route_tokens
                     #get_detail_route_tokens
                     #post_create_route_tokens
                     #patch_edit_route_tokens
                     #delete_route_tokens
+                    #(#child_route_tokens)*
                     #(.post_with_policy(#action_paths, #actions, #action_policies))*
                     #(.get_with_policy(#views_paths, #views, #view_policies))*
             }
@@ -210,3 +904,82 @@ pub fn derive_struct(input: &DeriveInput) -> TokenStream {
 
     parse_args_impl_tokens
 }
+
+#[cfg(test)]
+mod tests {
+    use darling::FromDeriveInput;
+    use syn::{parse_quote, DeriveInput, Ident};
+
+    use super::{pluralize, RoutesOptions};
+
+    fn routes_options(input: DeriveInput) -> RoutesOptions {
+        RoutesOptions::from_derive_input(&input).expect("Failed to parse #[routes(...)]")
+    }
+
+    #[test]
+    fn pluralizes_route_names() {
+        assert_eq!(pluralize("beer"), "beers");
+        assert_eq!(pluralize("brewery"), "breweries");
+        assert_eq!(pluralize("day"), "days");
+        assert_eq!(pluralize("key"), "keys");
+        assert_eq!(pluralize("box"), "boxes");
+        assert_eq!(pluralize("glass"), "glasses");
+        assert_eq!(pluralize("batch"), "batches");
+        assert_eq!(pluralize("brew_log_entry"), "brew_log_entries");
+    }
+
+    #[test]
+    fn route_path_defaults_to_the_pluralized_name() {
+        let ident: Ident = parse_quote!(BrewLogEntry);
+        assert_eq!(routes_options(parse_quote!(struct BrewLogEntry;)).route_path(&ident), "/brew_log_entries");
+    }
+
+    #[test]
+    fn route_path_trims_slashes() {
+        let ident: Ident = parse_quote!(Brewery);
+        let route_path = |input| routes_options(input).route_path(&ident);
+        assert_eq!(route_path(parse_quote!(#[routes(path = "api/v2/brews/")] struct Brewery;)), "/api/v2/brews");
+        assert_eq!(route_path(parse_quote!(#[routes(path = "/brews")] struct Brewery;)), "/brews");
+        assert_eq!(route_path(parse_quote!(#[routes(path = "/")] struct Brewery;)), "/");
+        assert_eq!(route_path(parse_quote!(#[routes(path = "")] struct Brewery;)), "/");
+    }
+
+    #[test]
+    fn every_route_is_enabled_by_default() {
+        let options = routes_options(parse_quote!(struct Brewery;));
+        assert!(options.validate_routes().is_ok());
+        assert!(["list", "get", "post", "patch", "delete"].iter().all(|route| options.is_enabled(route)));
+    }
+
+    #[test]
+    fn only_except_and_read_only_restrict_the_routes() {
+        let only = routes_options(parse_quote!(#[routes(only(list, post))] struct Brewery;));
+        assert!(only.validate_routes().is_ok());
+        assert!(only.is_enabled("list") && only.is_enabled("post"));
+        assert!(!only.is_enabled("get") && !only.is_enabled("patch") && !only.is_enabled("delete"));
+
+        let except = routes_options(parse_quote!(#[routes(except(delete))] struct Brewery;));
+        assert!(except.validate_routes().is_ok());
+        assert!(except.is_enabled("list") && except.is_enabled("patch"));
+        assert!(!except.is_enabled("delete"));
+
+        let read_only = routes_options(parse_quote!(#[routes(read_only)] struct Brewery;));
+        assert!(read_only.validate_routes().is_ok());
+        assert!(read_only.is_enabled("list") && read_only.is_enabled("get"));
+        assert!(!read_only.is_enabled("post") && !read_only.is_enabled("patch") && !read_only.is_enabled("delete"));
+    }
+
+    #[test]
+    fn restrictions_cant_be_combined() {
+        let options = routes_options(parse_quote!(#[routes(only(list), read_only)] struct Brewery;));
+        let error = options.validate_routes().expect_err("`only` and `read_only` were combined");
+        assert!(error.to_string().starts_with("Only one of"));
+    }
+
+    #[test]
+    fn unknown_routes_are_rejected() {
+        let options = routes_options(parse_quote!(#[routes(except(remove))] struct Brewery;));
+        let error = options.validate_routes().expect_err("`remove` isn't a route");
+        assert_eq!(error.to_string(), "Unknown route. Expected one of: list, get, post, patch, delete");
+    }
+}
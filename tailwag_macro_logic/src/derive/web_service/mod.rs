@@ -3,4 +3,5 @@ pub use rest_api::*;
 pub mod build_create_route;
 pub mod build_list_get_route;
 pub mod build_routes;
+mod openapi;
 // pub use build_routes::*;
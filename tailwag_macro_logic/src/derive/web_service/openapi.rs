@@ -0,0 +1,363 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{ext::IdentExt, Field, Ident, Path, Type};
+
+use super::build_routes::{is_generated_id, DEFAULT_LIST_LIMIT, MAX_LIST_LIMIT};
+use crate::{
+    derive::json_schema::{field_schema, object_schema, type_schema, SchemaContext},
+    util::{
+        attribute_parsing::GetAttribute,
+        type_parsing::{get_type_arguments, is_option},
+    },
+};
+
+/// The routes mounted by `BuildRoutes`, as described by the generated `openapi_paths()`.
+pub(crate) struct OpenApiRoutes<'a> {
+    /// The resource's struct, e.g. `Brewery`.
+    pub ident: &'a Ident,
+    /// The path the routes are documented under, e.g. `/breweries`.
+    pub route_path: String,
+    pub fields: Vec<&'a Field>,
+    pub id_field: &'a Field,
+    /// The CRUD routes being mounted (`list`, `get`, `post`, `patch`, `delete`), along with the custom handler for
+    /// any that are overridden.
+    pub crud_routes: Vec<(&'static str, Option<&'a Path>)>,
+    /// The nested child resources, e.g. `Beer` under `/{id}/beers`.
+    pub children: Vec<OpenApiChild>,
+    /// The paths of the `#[actions]` (mounted as POST) and `#[views]` (mounted as GET).
+    pub actions: &'a [String],
+    pub views: &'a [String],
+}
+
+/// A child resource, nested under its parent's routes by `#[children(...)]`.
+pub(crate) struct OpenApiChild {
+    /// The path the children are nested under, e.g. `/{id}/beers`.
+    pub path: String,
+    /// The child's type, e.g. `Beer`.
+    pub ty: Type,
+    /// The field on the child holding its parent's id, which is set from the path rather than the request body.
+    pub via: String,
+}
+
+/// A single operation in the spec, e.g. `GET /breweries/{id}`.
+struct Operation {
+    method: &'static str,
+    path: String,
+    summary: String,
+    parameters: Vec<TokenStream>,
+    request_body: Option<TokenStream>,
+    /// The status code and schema of a successful response. A `None` schema has no body.
+    response: (&'static str, Option<TokenStream>),
+}
+
+impl Operation {
+    fn new(
+        method: &'static str,
+        path: String,
+        summary: String,
+    ) -> Self {
+        Self {
+            method,
+            path,
+            summary,
+            parameters: Vec::new(),
+            request_body: None,
+            response: ("200", None),
+        }
+    }
+
+    fn to_tokens(&self) -> TokenStream {
+        let Self {
+            summary,
+            parameters,
+            ..
+        } = self;
+        let (status, schema) = &self.response;
+        let response = match schema {
+            Some(schema) => quote!({
+                let schema = #schema;
+                serde_json::json!({
+                    "description": "Success",
+                    "content": { "application/json": { "schema": schema } }
+                })
+            }),
+            None => quote!(serde_json::json!({ "description": "Success" })),
+        };
+        let request_body = self.request_body.as_ref().map(|schema| {
+            quote!(
+                let schema = #schema;
+                operation["requestBody"] = serde_json::json!({
+                    "required": true,
+                    "content": { "application/json": { "schema": schema } }
+                });
+            )
+        });
+        quote!({
+            let response = #response;
+            let mut operation = serde_json::json!({
+                "summary": #summary,
+                "responses": {
+                    #status: response,
+                    "default": {
+                        "description": "Error",
+                        "content": { "application/json": { "schema": error_schema.clone() } }
+                    }
+                }
+            });
+            let parameters: Vec<serde_json::Value> = vec![#(#parameters),*];
+            if !parameters.is_empty() {
+                operation["parameters"] = serde_json::Value::from(parameters);
+            }
+            #request_body
+            operation
+        })
+    }
+}
+
+/// Describes types in the spec the same way as the derived `JsonSchema`, except that types without a schema of their
+/// own (e.g. a foreign `Ulid`) accept anything, since `openapi_paths()` is generated for every resource. A field
+/// referring back to the resource itself accepts anything too, as there's no root schema to refer to mid-spec.
+fn schema_context(ident: &Ident) -> SchemaContext<'_> {
+    SchemaContext {
+        ident,
+        recursive: quote!(serde_json::json!({})),
+        allow_unknown: true,
+    }
+}
+
+fn path_parameter(
+    id_field: &Field,
+    context: &SchemaContext,
+) -> syn::Result<TokenStream> {
+    let schema = field_schema(id_field, context)?;
+    Ok(quote!({
+        let schema = #schema;
+        serde_json::json!({ "name": "id", "in": "path", "required": true, "schema": schema })
+    }))
+}
+
+fn query_parameter(
+    name: &str,
+    description: &str,
+    schema: TokenStream,
+) -> TokenStream {
+    quote!({
+        let schema = #schema;
+        serde_json::json!({ "name": #name, "in": "query", "description": #description, "schema": schema })
+    })
+}
+
+/// The `limit` / `offset` parameters of a list route.
+fn paging_parameters() -> [TokenStream; 2] {
+    let limit = quote!(serde_json::json!({
+        "type": "integer",
+        "minimum": 0,
+        "maximum": #MAX_LIST_LIMIT,
+        "default": #DEFAULT_LIST_LIMIT,
+    }));
+    let offset = quote!(serde_json::json!({ "type": "integer", "minimum": 0 }));
+    let description = format!("The maximum number of items to return, up to {}", MAX_LIST_LIMIT);
+    [query_parameter("limit", &description, limit), query_parameter("offset", "The number of items to skip", offset)]
+}
+
+/// Joins a route's relative path onto the resource's path, e.g. `/{id}` -> `/breweries/{id}`, or `/{id}` for
+/// resources mounted at the root (`/`).
+fn full_path(
+    route_path: &str,
+    path: &str,
+) -> String {
+    let path = path.trim_matches('/');
+    match path.is_empty() {
+        true => route_path.to_string(),
+        false => format!("{}/{}", route_path.trim_end_matches('/'), path),
+    }
+}
+
+/// Builds `fn openapi_paths() -> serde_json::Value`, which returns the OpenAPI 3.1 `paths` object describing every
+/// route mounted by `build_routes()`.
+pub(crate) fn build_openapi_paths(routes: &OpenApiRoutes) -> syn::Result<TokenStream> {
+    let OpenApiRoutes {
+        ident,
+        route_path,
+        fields,
+        id_field,
+        crud_routes,
+        children,
+        actions,
+        views,
+    } = routes;
+    let name = ident.to_string();
+    let context = schema_context(ident);
+    let id_name = id_field.ident.as_ref().map(|ident| ident.to_string()).unwrap_or_default();
+    let is_enabled = |route: &str| crud_routes.iter().any(|(name, _)| *name == route);
+
+    let create_fields: Vec<&Field> =
+        fields.iter().copied().filter(|f| !(is_generated_id(f) && f.ident == id_field.ident)).collect();
+    let patch_fields: Vec<&Field> = fields.iter().copied().filter(|f| f.ident != id_field.ident).collect();
+
+    let mut operations = Vec::new();
+    for (route, handler) in crud_routes {
+        let (method, path, summary) = match *route {
+            "list" => ("get", "/", format!("List {} items", name)),
+            "get" => ("get", "/{id}", format!("Get a {} by `{}`", name, id_name)),
+            "post" => ("post", "/", format!("Create a {}", name)),
+            "patch" => ("patch", "/{id}", format!("Update some fields of a {}", name)),
+            _ => ("delete", "/{id}", format!("Delete a {}", name)),
+        };
+        let mut operation = Operation::new(method, full_path(route_path, path), summary);
+        if path == "/{id}" {
+            operation.parameters.push(path_parameter(id_field, &context)?);
+        }
+        // The request and response of a custom handler are up to the handler, so they aren't described.
+        if let Some(handler) = handler {
+            operation.summary = format!("{} (handled by `{}`)", operation.summary, quote!(#handler));
+            operations.push(operation);
+            continue;
+        }
+        match *route {
+            "list" => {
+                let string = quote!(serde_json::json!({ "type": "string" }));
+                operation.parameters.extend(paging_parameters());
+                operation.parameters.extend([
+                    query_parameter(
+                        "after",
+                        "Continue after the item with this id. Can't be combined with `sort`",
+                        string.clone(),
+                    ),
+                ]);
+                let sortable: Vec<String> = fields
+                    .iter()
+                    .filter(|f| f.get_attribute("sortable").is_some())
+                    .filter_map(|f| f.ident.as_ref().map(|ident| format!("`{}`", ident)))
+                    .collect();
+                if !sortable.is_empty() {
+                    let description = format!(
+                        "Fields to sort by, comma-separated. Prefix with `-` to sort descending. One of: {}",
+                        sortable.join(", ")
+                    );
+                    operation.parameters.push(query_parameter("sort", &description, string));
+                }
+                for field in fields.iter().filter(|f| f.get_attribute("filterable").is_some()) {
+                    let Some(field_ident) = &field.ident else {
+                        continue;
+                    };
+                    // Optional fields are filtered by their inner value.
+                    let schema = match (is_option(field), field.get_attribute("json_schema")) {
+                        (true, None) => type_schema(
+                            get_type_arguments(&field.ty).first().copied().unwrap_or(&field.ty),
+                            &context,
+                        ),
+                        _ => field_schema(field, &context)?,
+                    };
+                    let name = field_ident.unraw().to_string();
+                    let description = format!("Only items with this `{}`", name);
+                    operation.parameters.push(query_parameter(&name, &description, schema));
+                }
+                operation.response.1 = Some(quote!(serde_json::json!({ "type": "array", "items": item_schema.clone() })));
+            },
+            "get" => operation.response.1 = Some(quote!(item_schema.clone())),
+            "post" => {
+                operation.request_body = Some(quote!(create_schema.clone()));
+                operation.response.1 = Some(quote!(item_schema.clone()));
+            },
+            "patch" => {
+                operation.request_body = Some(quote!(patch_schema.clone()));
+                operation.response.1 = Some(quote!(item_schema.clone()));
+            },
+            _ => operation.response.0 = "204",
+        }
+        operations.push(operation);
+    }
+
+    for child in children {
+        let path = full_path(route_path, &child.path);
+        let child_name = child.path.rsplit('/').next().unwrap_or_default();
+        let child_schema = type_schema(&child.ty, &context);
+        if is_enabled("list") {
+            let mut operation = Operation::new("get", path.clone(), format!("List the {} of a {}", child_name, name));
+            operation.parameters.push(path_parameter(id_field, &context)?);
+            operation.parameters.extend(paging_parameters());
+            operation.response.1 = Some(quote!({
+                let items = #child_schema;
+                serde_json::json!({ "type": "array", "items": items })
+            }));
+            operations.push(operation);
+        }
+        if is_enabled("post") {
+            let mut operation = Operation::new("post", path, format!("Create one of the {} of a {}", child_name, name));
+            operation.parameters.push(path_parameter(id_field, &context)?);
+            let via = &child.via;
+            // The parent's id always comes from the path, so it's left out of the request body.
+            operation.request_body = Some(quote!({
+                let mut schema = #child_schema;
+                if let Some(properties) = schema.get_mut("properties").and_then(|p| p.as_object_mut()) {
+                    properties.remove(#via);
+                }
+                if let Some(required) = schema.get_mut("required").and_then(|r| r.as_array_mut()) {
+                    required.retain(|name| name != #via);
+                }
+                schema
+            }));
+            operation.response.1 = Some(child_schema);
+            operations.push(operation);
+        }
+    }
+    for (method, kind, paths) in [("post", "action", actions), ("get", "view", views)] {
+        for path in paths.iter() {
+            let summary = format!("The `{}` {} of a {}", path.trim_matches('/'), kind, name);
+            operations.push(Operation::new(method, full_path(route_path, path), summary));
+        }
+    }
+
+    let item_schema = object_schema(fields, false, &context)?;
+    let create_schema = object_schema(&create_fields, false, &context)?;
+    let patch_schema = object_schema(&patch_fields, true, &context)?;
+    let paths = operations.iter().map(|operation| &operation.path);
+    let methods = operations.iter().map(|operation| operation.method);
+    let operation_tokens = operations.iter().map(Operation::to_tokens);
+    Ok(quote!(
+        /// The OpenAPI 3.1 `paths` object describing every route mounted by `build_routes()`.
+        pub fn openapi_paths() -> serde_json::Value {
+            // Not every schema is used when routes are overridden or left out.
+            #[allow(unused_variables)]
+            let item_schema = #item_schema;
+            #[allow(unused_variables)]
+            let create_schema = #create_schema;
+            #[allow(unused_variables)]
+            let patch_schema = #patch_schema;
+            #[allow(unused_variables)]
+            let error_schema = serde_json::json!({
+                "type": "object",
+                "properties": { "error": { "type": "string" } },
+                "required": ["error"]
+            });
+
+            let mut paths = serde_json::Map::new();
+            #(
+                if let Some(path) = paths.entry(#paths).or_insert_with(|| serde_json::json!({})).as_object_mut() {
+                    path.insert(#methods.to_string(), #operation_tokens);
+                }
+            )*
+            serde_json::Value::Object(paths)
+        }
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::full_path;
+
+    #[test]
+    fn joins_paths_with_a_single_slash() {
+        assert_eq!(full_path("/breweries", "/{id}"), "/breweries/{id}");
+        assert_eq!(full_path("/breweries", "{id}/beers/"), "/breweries/{id}/beers");
+        assert_eq!(full_path("/", "/{id}"), "/{id}");
+    }
+
+    #[test]
+    fn empty_paths_are_the_route_path() {
+        assert_eq!(full_path("/breweries", ""), "/breweries");
+        assert_eq!(full_path("/breweries", "/"), "/breweries");
+        assert_eq!(full_path("/", "/"), "/");
+    }
+}
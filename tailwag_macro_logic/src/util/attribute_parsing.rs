@@ -1,4 +1,7 @@
-use syn::{AttrStyle, Attribute, DeriveInput, Field};
+use syn::{
+    punctuated::Punctuated, AttrStyle, Attribute, DeriveInput, Expr, ExprAssign, ExprLit, ExprPath, Field, Ident,
+    Lit, LitStr, Meta, Token, Variant,
+};
 
 pub trait GetAttribute<'a> {
     fn get_attribute(
@@ -30,3 +33,63 @@ impl<'a> GetAttribute<'a> for DeriveInput {
             .find(|a| a.path().is_ident(attr_name))
     }
 }
+
+impl<'a> GetAttribute<'a> for Variant {
+    fn get_attribute(
+        &'a self,
+        attr_name: &str,
+    ) -> Option<&'a Attribute> {
+        self.attrs
+            .iter()
+            .filter(|a| a.style == AttrStyle::Outer)
+            .find(|a| a.path().is_ident(attr_name))
+    }
+}
+
+/// Parses the comma-separated arguments of an attribute as expressions.
+///
+/// Bare attributes (`#[display]`) have no arguments and return an empty list, while
+/// `#[display(payload, rename = "name")]` returns the `payload` path and the `rename = "name"` assignment.
+pub fn get_attribute_args(attr: &Attribute) -> syn::Result<Vec<Expr>> {
+    match &attr.meta {
+        Meta::Path(_) => Ok(Vec::new()),
+        _ => Ok(attr
+            .parse_args_with(Punctuated::<Expr, Token![,]>::parse_terminated)?
+            .into_iter()
+            .collect()),
+    }
+}
+
+/// Returns the identifier of a bare flag argument, e.g. `payload` in `#[display(payload)]`.
+pub fn as_flag(expr: &Expr) -> Option<&Ident> {
+    match expr {
+        Expr::Path(ExprPath {
+            path,
+            ..
+        }) => path.get_ident(),
+        _ => None,
+    }
+}
+
+/// Splits a `key = value` argument into its key and value, e.g. `rename = "name"` in `#[display(rename = "name")]`.
+pub fn as_key_value(expr: &Expr) -> Option<(&Ident, &Expr)> {
+    match expr {
+        Expr::Assign(ExprAssign {
+            left,
+            right,
+            ..
+        }) => as_flag(left).map(|key| (key, right.as_ref())),
+        _ => None,
+    }
+}
+
+/// Extracts the string from a string literal argument, e.g. `"name"` in `#[display(rename = "name")]`.
+pub fn as_lit_str(expr: &Expr) -> Option<&LitStr> {
+    match expr {
+        Expr::Lit(ExprLit {
+            lit: Lit::Str(lit),
+            ..
+        }) => Some(lit),
+        _ => None,
+    }
+}
@@ -1,4 +1,5 @@
 pub mod attribute_parsing;
+pub mod rename_rule;
 pub mod type_parsing;
 
 // mod derive_logic;
@@ -0,0 +1,54 @@
+use syn::LitStr;
+use tailwag_utils::strings::to_snake_case;
+
+/// A casing rule applied to identifiers, e.g. `#[display(rename_all = "kebab-case")]`.
+#[derive(Clone, Copy)]
+pub enum RenameRule {
+    /// `InProgress` -> `in_progress`
+    Snake,
+    /// `InProgress` -> `in-progress`
+    Kebab,
+    /// `InProgress` -> `IN_PROGRESS`
+    ScreamingSnake,
+    /// `InProgress` -> `In Progress`
+    Title,
+}
+
+impl RenameRule {
+    pub fn parse(lit: &LitStr) -> syn::Result<Self> {
+        match lit.value().as_str() {
+            "snake_case" => Ok(Self::Snake),
+            "kebab-case" => Ok(Self::Kebab),
+            "SCREAMING_SNAKE_CASE" => Ok(Self::ScreamingSnake),
+            "Title Case" => Ok(Self::Title),
+            _ => Err(syn::Error::new(
+                lit.span(),
+                "Unknown casing rule. Expected one of: \"snake_case\", \"kebab-case\", \"SCREAMING_SNAKE_CASE\", \"Title Case\"",
+            )),
+        }
+    }
+
+    pub fn apply(
+        &self,
+        ident: &str,
+    ) -> String {
+        let snake_case = to_snake_case(ident);
+        match self {
+            Self::Snake => snake_case,
+            Self::Kebab => snake_case.replace('_', "-"),
+            Self::ScreamingSnake => snake_case.to_uppercase(),
+            Self::Title => snake_case
+                .split('_')
+                .filter(|word| !word.is_empty())
+                .map(|word| {
+                    let mut chars = word.chars();
+                    chars
+                        .next()
+                        .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                        .unwrap_or_default()
+                })
+                .collect::<Vec<_>>()
+                .join(" "),
+        }
+    }
+}
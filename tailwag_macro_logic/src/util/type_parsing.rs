@@ -50,6 +50,112 @@ pub fn is_option(field: &Field) -> bool {
     }
 }
 
+/// Gives you the generic type arguments of a type, e.g. `[T]` for `Vec<T>` or `[K, V]` for `HashMap<K, V>`.
+///
+/// Only the last path segment is inspected, and lifetimes / const arguments are skipped.
+pub fn get_type_arguments(ty: &Type) -> Vec<&Type> {
+    let syn::Type::Path(typepath) = ty else {
+        return Vec::new();
+    };
+    match typepath.path.segments.last().map(|segment| &segment.arguments) {
+        Some(PathArguments::AngleBracketed(params)) => params
+            .args
+            .iter()
+            .filter_map(|arg| match arg {
+                GenericArgument::Type(ty) => Some(ty),
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Gives you the JSON Schema `type` (and `format`, if it has one) that a primitive type serializes to,
+/// e.g. `("integer", Some("int64"))` for `i64`. Returns `None` for any other type.
+pub fn get_json_schema_type(ty: &Type) -> Option<(&'static str, Option<&'static str>)> {
+    let typepath = match ty {
+        syn::Type::Path(typepath) => typepath,
+        syn::Type::Reference(reference) => return get_json_schema_type(&reference.elem),
+        _ => return None,
+    };
+    let schema_type = match typepath.path.segments.last()?.ident.to_string().as_str() {
+        "String" | "str" | "char" => ("string", None),
+        "bool" => ("boolean", None),
+        "i8" | "i16" | "i32" | "u8" | "u16" | "u32" => ("integer", Some("int32")),
+        "i64" | "i128" | "isize" | "u64" | "u128" | "usize" => ("integer", Some("int64")),
+        "f32" => ("number", Some("float")),
+        "f64" => ("number", Some("double")),
+        "Uuid" => ("string", Some("uuid")),
+        "NaiveDate" => ("string", Some("date")),
+        "NaiveDateTime" | "DateTime" => ("string", Some("date-time")),
+        _ => return None,
+    };
+    Some(schema_type)
+}
+
+/// The shape a type takes once serialized to JSON, used to build its JSON Schema.
+pub enum JsonShape<'a> {
+    /// A primitive with a known JSON Schema `type` (and `format`), from [get_json_schema_type].
+    Primitive(&'static str, Option<&'static str>),
+    /// `Option<T>` - `T`, or `null`.
+    Optional(&'a Type),
+    /// `Vec<T>`, `[T]`, `HashSet<T>`, etc. Sets also have `unique` items.
+    Array {
+        items: &'a Type,
+        unique: bool,
+    },
+    /// `HashMap<K, V>` / `BTreeMap<K, V>` - an object whose values are `V`.
+    Map(&'a Type),
+    /// `serde_json::Value`, which can be anything.
+    Any,
+    /// Any other type, e.g. a nested struct or enum, which describes its own schema.
+    Nested(&'a Type),
+}
+
+/// Works out the [JsonShape] of a type. References and smart pointers (`Box`, `Rc`, `Arc`) are looked through.
+pub fn get_json_shape(ty: &Type) -> JsonShape<'_> {
+    if let Some((schema_type, format)) = get_json_schema_type(ty) {
+        return JsonShape::Primitive(schema_type, format);
+    }
+    let typepath = match ty {
+        syn::Type::Path(typepath) => typepath,
+        syn::Type::Reference(reference) => return get_json_shape(&reference.elem),
+        syn::Type::Paren(paren) => return get_json_shape(&paren.elem),
+        syn::Type::Slice(slice) => {
+            return JsonShape::Array {
+                items: &slice.elem,
+                unique: false,
+            }
+        },
+        syn::Type::Array(array) => {
+            return JsonShape::Array {
+                items: &array.elem,
+                unique: false,
+            }
+        },
+        _ => return JsonShape::Nested(ty),
+    };
+    let Some(segment) = typepath.path.segments.last() else {
+        return JsonShape::Nested(ty);
+    };
+    let type_args = get_type_arguments(ty);
+    match (segment.ident.to_string().as_str(), type_args.as_slice()) {
+        ("Option", [inner]) => JsonShape::Optional(inner),
+        ("Box" | "Rc" | "Arc" | "Cow", [inner]) => get_json_shape(inner),
+        ("Vec" | "VecDeque" | "LinkedList", [items]) => JsonShape::Array {
+            items,
+            unique: false,
+        },
+        ("HashSet" | "BTreeSet" | "IndexSet", [items]) => JsonShape::Array {
+            items,
+            unique: true,
+        },
+        ("HashMap" | "BTreeMap" | "IndexMap", [_, value]) => JsonShape::Map(value),
+        ("Value", []) => JsonShape::Any,
+        _ => JsonShape::Nested(ty),
+    }
+}
+
 /// Gives you the primary type of the field. If it's an Option, then this will return the qualified path string for the Option's inner type.
 /// If not, it returns the qualified path string for the entire type.
 pub fn extract_option_type(field: &Field) -> String {